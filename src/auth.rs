@@ -1,17 +1,23 @@
 use std::sync::OnceLock;
 
+use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
 
-use crate::{config, keychain::CredentialStore};
+use crate::{
+    callback::{self, CallbackServer},
+    client,
+    config::{self, AppConfig, AuthMode, OAuthConfig},
+    error::{Error, Result},
+    keychain::CredentialStore,
+    oauth::{self, TokenResponse},
+};
 
 static API_CLIENT: OnceLock<Client> = OnceLock::new();
 
-#[derive(Debug, Deserialize, Serialize)]
-struct TokenResponse {
-    access_token: String,
-}
+/// Tokens are refreshed this many seconds before they expire, so a command doesn't outlive them
+const REFRESH_MARGIN_SECS: i64 = 300;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct AuthResponse {
     redirect: String,
@@ -28,77 +34,256 @@ fn get_client() -> &'static Client {
     })
 }
 
-async fn exchange_token(
-    req: String,
-    email: String,
-) -> Result<TokenResponse, Box<dyn std::error::Error>> {
-    let url = format!(
-        "{}/auth/token?req={}&email={}",
-        &config::get().auth_host,
-        req,
-        email
-    );
+async fn exchange_token(req: &str, email: &str, code: Option<&str>) -> Result<TokenResponse> {
+    let mut query = vec![("req", req), ("email", email)];
+    if let Some(code) = code {
+        query.push(("code", code));
+    }
 
-    let res = get_client().get(url).send().await?;
+    let res = get_client()
+        .get(format!("{}/auth/token", auth_host()?))
+        .query(&query)
+        .send()
+        .await?;
 
     let status = res.status();
 
     if !status.is_success() {
         let error_text = res.text().await?;
-        return Err(format!("Token Exchange falied: {}", error_text).into());
+        return Err(Error::Auth(format!(
+            "token exchange failed: {}",
+            error_text
+        )));
     }
 
     let token_response: TokenResponse = res.json().await?;
     Ok(token_response)
 }
 
-pub async fn authenticate(email: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Authenticating 🔐...");
+async fn refresh_with_proxy(email: &str, refresh_token: &str) -> Result<TokenResponse> {
+    let res = get_client()
+        .get(format!("{}/auth/refresh", auth_host()?))
+        .query(&[("refresh_token", refresh_token), ("email", email)])
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error_text = res.text().await?;
+        return Err(Error::Auth(format!("token refresh failed: {}", error_text)));
+    }
+
+    let token_response: TokenResponse = res.json().await?;
+    Ok(token_response)
+}
 
-    if email.is_none() {
-        eprint!("Please set your email with <command> login --email=<your email>");
-        return Ok(());
+async fn revoke_with_proxy(email: &str, token: &str) -> Result<()> {
+    let res = get_client()
+        .get(format!("{}/auth/revoke", auth_host()?))
+        .query(&[("token", token), ("email", email)])
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error_text = res.text().await?;
+        return Err(Error::Auth(format!(
+            "token revocation failed: {}",
+            error_text
+        )));
     }
+    Ok(())
+}
 
-    let user_email = email.unwrap();
+/// The access token of `email`, refreshed first when it expires soon.
+/// A token that can't be refreshed any more means logging in again.
+pub async fn access_token(email: &str, oauth: &OAuthConfig) -> Result<String> {
+    let token = CredentialStore::get(email)?;
+    if !token.expires_within(REFRESH_MARGIN_SECS) {
+        return Ok(token.access_token);
+    }
+
+    let refreshed = match (&token.refresh_token, oauth.mode) {
+        (None, _) => Err(Error::AuthExpired),
+        (Some(refresh_token), AuthMode::Proxy) => refresh_with_proxy(email, refresh_token).await,
+        (Some(refresh_token), AuthMode::Direct) => match CredentialStore::get_client(email)? {
+            Some(client) => oauth::refresh_token(&client, refresh_token).await,
+            None => Err(Error::AuthExpired),
+        },
+    };
+
+    match refreshed {
+        Ok(response) => {
+            let refreshed = response.into_token(token.refresh_token);
+            CredentialStore::update(email, &refreshed)?;
+            Ok(refreshed.access_token)
+        }
+        // Still valid for a few minutes, the next command tries again
+        Err(_) if !token.expires_within(0) => Ok(token.access_token),
+        Err(e @ Error::Network(_)) => Err(e),
+        Err(_) => Err(Error::AuthExpired),
+    }
+}
+
+fn auth_host() -> Result<&'static str> {
+    config::get().auth_host.as_deref().ok_or_else(|| {
+        Error::Config(String::from(
+            "AUTH_HOST is missing. Set it, or log in with your own app: tick config --auth-mode direct",
+        ))
+    })
+}
 
-    let url = format!(
-        "{}/auth/authorize?email={}",
-        &config::get().auth_host,
-        user_email
+/// Manual end of the login: the user pastes where the browser landed, or confirms with ENTER
+fn print_manual_prompt() {
+    println!(
+        "\nWhen you're done in the browser, paste the URL it was redirected to (or the code),"
     );
+    println!("or press ENTER if the page said the login is complete:");
+}
 
-    //run local server to listen callback
+/// Log in and store the token. `headless` skips the browser and the local listener,
+/// the URL is printed and the redirect pasted back.
+pub async fn authenticate(cfg: &AppConfig, headless: bool) -> Result<()> {
+    let user_email = cfg.require_email()?;
+    println!("Authenticating 🔐...");
 
-    let auth_res = get_client().get(url).send().await?;
+    if cfg.oauth.mode == AuthMode::Direct {
+        return oauth::login(user_email, cfg.oauth.redirect_port, headless).await;
+    }
+
+    // The browser is redirected back to this listener once authorized,
+    // without it the user confirms in the terminal
+    let callback = if headless {
+        None
+    } else {
+        match CallbackServer::bind(0).await {
+            Ok(server) => Some(server),
+            Err(e) => {
+                eprintln!("Cannot listen for the login callback: {}", e);
+                None
+            }
+        }
+    };
+
+    let mut query = vec![("email", user_email)];
+    if let Some(server) = &callback {
+        query.push(("redirect_uri", &server.redirect_uri));
+        query.push(("state", &server.state));
+    }
+
+    let auth_res = get_client()
+        .get(format!("{}/auth/authorize", auth_host()?))
+        .query(&query)
+        .send()
+        .await?;
 
     if !auth_res.status().is_success() {
         let error_text = auth_res.text().await?;
-        return Err(format!("Auth falied: {}", error_text).into());
+        return Err(Error::Auth(error_text));
     }
 
     let data_response: AuthResponse = auth_res.json().await?;
 
-    // This function automatically handles URL encoding of parameter names and values.
-
-    let browser = open::that(data_response.redirect.clone());
-    if browser.is_err() {
-        println!("Opening browser to: {}", data_response.redirect.clone());
-        eprintln!("Could not automatically open browser. Please visit the URL above manually.");
+    let browser_opened = !headless && open::that(&data_response.redirect).is_ok();
+    if !browser_opened {
+        println!("Open this URL in a browser: {}", data_response.redirect);
+        if !headless {
+            eprintln!("Could not automatically open browser. Please visit the URL above manually.");
+        }
     }
-    println!("\nPress ENTER when you're done in the browser...");
-
-    io::stdout().flush()?;
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    // A headless machine can't receive the redirect, the browser runs elsewhere
+    let state = callback.as_ref().map(|server| server.state.clone());
+    let code = match callback {
+        Some(server) if browser_opened => {
+            println!("Waiting for the browser to complete the login...");
+            print_manual_prompt();
+            callback::wait_or_paste(server, callback::CALLBACK_TIMEOUT).await?
+        }
+        _ => {
+            print_manual_prompt();
+            callback::read_pasted_code(state.as_deref())?
+        }
+    };
 
     println!("Verifying token...");
-    let token_res: TokenResponse = exchange_token(data_response.req, data_response.email).await?;
+    let token_res: TokenResponse =
+        exchange_token(&data_response.req, &data_response.email, code.as_deref()).await?;
 
-    let _ = CredentialStore::save(&user_email, &token_res.access_token)?;
+    CredentialStore::save(user_email, &token_res.into_token(None))?;
 
     println!("✅ Auth completed");
 
     Ok(())
 }
+
+/// Rough length of a time span, e.g. "3d" or "45m"
+fn describe_span(seconds: i64) -> String {
+    let seconds = seconds.abs();
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 60 * 60 * 24 => format!("{}h", s / (60 * 60)),
+        s => format!("{}d", s / (60 * 60 * 24)),
+    }
+}
+
+/// Print the login state and check the saved token against the API.
+/// Fails when there is no usable token, so scripts can rely on the exit code.
+pub async fn status(cfg: &AppConfig) -> Result<()> {
+    println!("Email:     {}", cfg.email.as_deref().unwrap_or("-"));
+    println!("Auth mode: {:?}", cfg.oauth.mode);
+    let email = cfg.require_email()?;
+
+    let token = CredentialStore::get(email)?;
+    let now = Utc::now().timestamp();
+
+    let age = token.saved_at.map_or(String::from("unknown"), |saved_at| {
+        format!("{} ago", describe_span(now - saved_at))
+    });
+    let expiry = match token.expires_at {
+        Some(expires_at) if expires_at > now => format!("in {}", describe_span(expires_at - now)),
+        Some(expires_at) => format!("{} ago", describe_span(now - expires_at)),
+        None => String::from("unknown"),
+    };
+    let refresh = if token.refresh_token.is_some() {
+        "yes"
+    } else {
+        "no"
+    };
+    println!("Token:     saved {}", age);
+    println!("Expires:   {}", expiry);
+    println!("Refresh:   {}", refresh);
+
+    client::build_client(&token.access_token, &cfg.retry)?
+        .get_projects()
+        .await?;
+    println!("API:       ✅ token accepted");
+    Ok(())
+}
+
+/// Delete the saved token, `revoke` first asks the server to invalidate it
+pub async fn logout(cfg: &AppConfig, revoke: bool) -> Result<()> {
+    let email = cfg.require_email()?;
+
+    if revoke {
+        let token = CredentialStore::get(email)?;
+        let revoked = match cfg.oauth.mode {
+            AuthMode::Proxy => revoke_with_proxy(email, &token.access_token).await,
+            AuthMode::Direct => match CredentialStore::get_client(email)? {
+                Some(client) => oauth::revoke_token(&client, &token.access_token).await,
+                None => Err(Error::Auth(String::from("no developer app saved"))),
+            },
+        };
+        match revoked {
+            Ok(_) => println!("✅ Token revoked"),
+            // The local copy goes away regardless, it can't be used without the keychain
+            Err(e) => eprintln!("⚠️  Could not revoke the token: {}", e),
+        }
+    }
+
+    if CredentialStore::delete(email)? {
+        println!("✅ Logged out: {}", email);
+    } else {
+        println!("Not logged in: {}", email);
+    }
+    Ok(())
+}
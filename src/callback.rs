@@ -0,0 +1,279 @@
+//! Short-lived HTTP listener on a random loopback port that receives the OAuth
+//! redirect, so the browser hands the authorization code straight back to the CLI.
+//! Headless logins paste the redirect URL instead.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    net::Ipv4Addr,
+    thread,
+    time::Duration,
+};
+
+use tokio::{
+    net::TcpListener,
+    sync::{mpsc, oneshot},
+};
+use url::Url;
+use warp::{http::StatusCode, Filter};
+
+use crate::error::{Error, Result};
+
+const CALLBACK_PATH: &str = "callback";
+
+/// How long the local listener waits for the browser before the login falls back to the terminal
+pub const CALLBACK_TIMEOUT: Duration = Duration::from_secs(180);
+
+pub struct CallbackServer {
+    listener: TcpListener,
+    pub redirect_uri: String,
+    /// Nonce sent with the authorization request, the redirect must carry it back
+    pub state: String,
+}
+
+/// Where the browser is sent back to, for a callback server on `port`
+pub fn redirect_uri(port: u16) -> String {
+    format!("http://127.0.0.1:{}/{}", port, CALLBACK_PATH)
+}
+
+fn page(message: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><title>tick-cli</title></head>\
+         <body><p>{}</p></body></html>",
+        message
+    )
+}
+
+impl CallbackServer {
+    /// Listen on 127.0.0.1, port 0 lets the OS choose a free one
+    pub async fn bind(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .await
+            .map_err(|e| Error::Io(format!("Cannot listen on port {}: {}", port, e)))?;
+        let port = listener.local_addr()?.port();
+
+        Ok(CallbackServer {
+            listener,
+            redirect_uri: redirect_uri(port),
+            state: uuid::Uuid::new_v4().to_string(),
+        })
+    }
+
+    /// Serve until a redirect with the right `state` arrives, then shut down.
+    /// Returns the authorization code, or `None` when nothing arrived before `timeout`.
+    pub async fn wait(self, timeout: Duration) -> Result<Option<String>> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let state = self.state;
+
+        let route = warp::get()
+            .and(warp::path(CALLBACK_PATH))
+            .and(warp::path::end())
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |params: HashMap<String, String>| {
+                // Anything without our nonce didn't come from the login we started
+                if params.get("state") != Some(&state) {
+                    let reply = warp::reply::html(page("Invalid login state, please try again."));
+                    return warp::reply::with_status(reply, StatusCode::BAD_REQUEST);
+                }
+
+                let (result, message) = match (params.get("code"), params.get("error")) {
+                    (Some(code), _) => (
+                        Ok(code.clone()),
+                        "✅ Login complete, you can close this tab.",
+                    ),
+                    (None, error) => (
+                        Err(error.cloned().unwrap_or(String::from("no code received"))),
+                        "❌ Login failed, check the terminal.",
+                    ),
+                };
+                sender.send(result).ok();
+                warp::reply::with_status(warp::reply::html(page(message)), StatusCode::OK)
+            });
+
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let server = warp::serve(route)
+            .incoming(self.listener)
+            .graceful(async {
+                signal.await.ok();
+            })
+            .run();
+
+        let waiting = async {
+            let received = tokio::time::timeout(timeout, receiver.recv()).await;
+            shutdown.send(()).ok();
+            received
+        };
+
+        let ((), received) = tokio::join!(server, waiting);
+        match received {
+            Ok(Some(Ok(code))) => Ok(Some(code)),
+            Ok(Some(Err(error))) => Err(Error::Auth(format!("authorization denied: {}", error))),
+            Ok(None) | Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Read one line from the terminal on a detached thread. A blocking tokio task
+/// would keep the runtime from shutting down when the callback arrives first.
+fn read_line() -> oneshot::Receiver<io::Result<String>> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let mut input = String::new();
+        let result = io::stdin().read_line(&mut input).map(|_| input);
+        sender.send(result).ok();
+    });
+    receiver
+}
+
+/// Wait for the callback while the user can already paste the redirect, or press ENTER,
+/// in the terminal. Whichever answers first wins, after `timeout` only the terminal is read.
+pub async fn wait_or_paste(server: CallbackServer, timeout: Duration) -> Result<Option<String>> {
+    let state = server.state.clone();
+    let mut line = read_line();
+
+    let input = tokio::select! {
+        received = server.wait(timeout) => match received? {
+            Some(code) => return Ok(Some(code)),
+            None => {
+                eprintln!("No callback received after {} seconds", timeout.as_secs());
+                (&mut line).await
+            }
+        },
+        input = &mut line => input,
+    };
+    let input = input.map_err(|_| Error::Io(String::from("Cannot read from the terminal")))??;
+
+    match parse_redirect(&input, Some(&state)) {
+        Err(Error::Validation(message)) => {
+            eprintln!("❌ {}, try again", message);
+            read_pasted_code(Some(&state))
+        }
+        result => result,
+    }
+}
+
+/// Take the authorization code out of a pasted redirect URL, anything else is the code itself.
+/// `None` when nothing was pasted. A URL must carry the expected `state` back.
+pub fn parse_redirect(input: &str, state: Option<&str>) -> Result<Option<String>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let url = match Url::parse(input) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+        _ if input.chars().any(char::is_whitespace) => {
+            return Err(Error::Validation(String::from(
+                "Paste either the full redirect URL or only the code",
+            )))
+        }
+        _ => return Ok(Some(input.to_string())),
+    };
+
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    if let Some(error) = params.get("error") {
+        return Err(Error::Auth(format!("authorization denied: {}", error)));
+    }
+    if let Some(expected) = state {
+        if params.get("state").map(String::as_str) != Some(expected) {
+            return Err(Error::Validation(String::from(
+                "The URL belongs to another login attempt",
+            )));
+        }
+    }
+
+    params
+        .get("code")
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| Error::Validation(String::from("The URL has no code parameter")))
+}
+
+/// Read a pasted redirect URL or code from the terminal, asking again when it can't be used
+pub fn read_pasted_code(state: Option<&str>) -> Result<Option<String>> {
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match parse_redirect(&input, state) {
+            Err(Error::Validation(message)) => eprintln!("❌ {}, try again", message),
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATE: &str = "1b4e28ba-2fa1-11d2-883f-0016d3cca427";
+
+    fn redirect(query: &str) -> String {
+        format!("{}?{}", redirect_uri(8400), query)
+    }
+
+    #[test]
+    fn takes_the_code_from_a_redirect_url() {
+        let url = redirect(&format!("code=abc123&state={}", STATE));
+        assert_eq!(
+            parse_redirect(&url, Some(STATE)).unwrap().as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            parse_redirect(&format!("  {}\n", url), None)
+                .unwrap()
+                .as_deref(),
+            Some("abc123")
+        );
+    }
+
+    #[test]
+    fn rejects_a_url_from_another_login() {
+        let other = redirect("code=abc123&state=other");
+        let missing = redirect("code=abc123");
+        for url in [other, missing] {
+            assert!(matches!(
+                parse_redirect(&url, Some(STATE)),
+                Err(Error::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn reports_a_denied_authorization() {
+        let url = redirect(&format!("error=access_denied&state={}", STATE));
+        match parse_redirect(&url, Some(STATE)) {
+            Err(Error::Auth(message)) => assert!(message.contains("access_denied")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_a_url_without_a_code() {
+        let url = redirect(&format!("state={}", STATE));
+        assert!(matches!(
+            parse_redirect(&url, Some(STATE)),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn accepts_a_bare_code() {
+        assert_eq!(
+            parse_redirect("abc123", Some(STATE)).unwrap().as_deref(),
+            Some("abc123")
+        );
+        assert!(matches!(
+            parse_redirect("abc 123", None),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn returns_nothing_for_an_empty_line() {
+        assert_eq!(parse_redirect("\n", Some(STATE)).unwrap(), None);
+    }
+}
@@ -5,14 +5,24 @@ use reqwest::{
     Client,
 };
 
-static API_CLIENT: OnceLock<Client> = OnceLock::new();
+use crate::{
+    config::{self, RetryConfig},
+    error::{Error, Result},
+    tick_tick_api::{RetryPolicy, TickTickClient},
+};
+
+static API_CLIENT: OnceLock<TickTickClient> = OnceLock::new();
 
-pub fn init_client(token: &str) -> Result<(), reqwest::Error> {
+/// An API client sending `token`, for one-off checks that shouldn't replace the shared client
+pub fn build_client(token: &str, retry: &RetryConfig) -> Result<TickTickClient> {
     let mut headers = header::HeaderMap::new();
-    print!("token {:?}\n", token); //TODO: Remove this
     headers.insert(
         header::AUTHORIZATION,
-        header::HeaderValue::from_str(&format!("Bearer {}", token)).expect("Invalid token"),
+        header::HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| {
+            Error::Auth(String::from(
+                "the saved token is malformed. Run: tick auth login",
+            ))
+        })?,
     );
 
     let client = Client::builder()
@@ -20,10 +30,18 @@ pub fn init_client(token: &str) -> Result<(), reqwest::Error> {
         .timeout(Duration::from_secs(30))
         .build()?;
 
-    API_CLIENT.set(client).ok();
+    let retry = RetryPolicy {
+        max_attempts: retry.max_attempts,
+        backoff: Duration::from_millis(retry.backoff_ms),
+    };
+    Ok(TickTickClient::new(client, &config::get().api_host).with_retry(retry))
+}
+
+pub fn init_client(token: &str, retry: &RetryConfig) -> Result<()> {
+    API_CLIENT.set(build_client(token, retry)?).ok();
     Ok(())
 }
 
-pub fn client() -> &'static Client {
+pub fn client() -> &'static TickTickClient {
     API_CLIENT.get().expect("Client not initialized")
 }
@@ -1,13 +1,19 @@
-use std::{env, error::Error, sync::OnceLock};
+use std::{env, sync::OnceLock};
 
+use clap::ValueEnum;
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
+
 #[derive(Debug)]
 pub struct Config {
     pub app_name: String,
     pub api_host: String,
-    pub auth_host: String,
+    /// OAuth proxy owning the client secret, only needed by the proxy login
+    pub auth_host: Option<String>,
+    /// TickTick's own OAuth endpoints, used by the direct login
+    pub oauth_host: String,
     pub time_zone: String,
 }
 
@@ -15,6 +21,53 @@ pub struct Config {
 pub struct AppConfig {
     pub email: Option<String>,
     pub default_project: String,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub oauth: OAuthConfig,
+}
+
+/// How `tick auth login` obtains a token
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthMode {
+    /// Through the AUTH_HOST proxy, which owns the client secret
+    #[default]
+    Proxy,
+    /// Authorization code + PKCE against TickTick with your own developer app
+    Direct,
+}
+
+/// Login settings, `redirect_port` must match the redirect URL of the developer app
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    pub mode: AuthMode,
+    pub redirect_port: u16,
+}
+
+impl Default for OAuthConfig {
+    fn default() -> Self {
+        OAuthConfig {
+            mode: AuthMode::Proxy,
+            redirect_port: 8400,
+        }
+    }
+}
+
+/// Retries of idempotent API requests, `max_attempts` counts the first try
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            backoff_ms: 500,
+        }
+    }
 }
 
 pub static CONFIG: OnceLock<Config> = OnceLock::new();
@@ -26,15 +79,25 @@ impl Config {
         Config {
             app_name: String::from("tick-cli"),
             api_host: env::var("API_HOST").expect("API_HOST is missing"),
-            auth_host: env::var("AUTH_HOST").expect("AUTH_HOST is missing"),
+            auth_host: env::var("AUTH_HOST").ok(),
+            oauth_host: env::var("OAUTH_HOST").unwrap_or(String::from("https://ticktick.com")),
             time_zone: iana_time_zone::get_timezone().unwrap_or(String::from("")),
         }
     }
 }
 
 impl AppConfig {
+    /// The configured email, it keys every keychain entry
+    pub fn require_email(&self) -> Result<&str> {
+        self.email.as_deref().ok_or_else(|| {
+            Error::Config(String::from(
+                "No email configured. Run: tick config --email <your-email>",
+            ))
+        })
+    }
+
     /// Load the user config from disk
-    pub fn load() -> Result<Self, Box<dyn Error>> {
+    pub fn load() -> Result<Self> {
         let cfg: AppConfig = confy::load(&get().app_name, None)?;
         Ok(cfg)
     }
@@ -48,7 +111,7 @@ impl AppConfig {
     ///     cfg.default_project = Some("work".to_string());
     /// })?;
     /// ```
-    pub fn update<F>(updater: F) -> Result<(), Box<dyn Error>>
+    pub fn update<F>(updater: F) -> Result<()>
     where
         F: FnOnce(&mut AppConfig),
     {
@@ -0,0 +1,525 @@
+//! Date parsing shared by the task commands: absolute dates, time ranges,
+//! natural language ("tomorrow 3pm", "next friday", "in 2 hours") and ISO-8601.
+
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::error::{Error, Result};
+
+const ACCEPTED_FORMATS: &str = "Accepted formats:
+  2025-12-25 | 2025-12-16 2:00pm | 2025-12-16 14:00 | 2025-12-16 2pm-4pm
+  today | tomorrow 3pm | friday | next friday at 10am | next week | next month
+  in 30 minutes | in 2 hours | in 3 days | in 2 weeks | in 1 month
+  end of week | end of month | end of year
+  june 5 | 5th june | 12/25 | 12/25/2026
+  2025-12-16T14:00:00+02:00 (ISO-8601 with offset)";
+
+/// A parsed date, ranges like "2025-12-16 2pm-4pm" also carry a start
+pub struct DateSpan {
+    pub start: Option<DateTime<Tz>>,
+    pub end: DateTime<Tz>,
+}
+
+impl DateSpan {
+    fn at(end: DateTime<Tz>) -> DateSpan {
+        DateSpan { start: None, end }
+    }
+}
+
+/// Format a date the way the open API expects it
+pub fn to_api_date(date: &DateTime<Tz>) -> String {
+    let utc_dt = date.with_timezone(&chrono_tz::UTC);
+    utc_dt.format("%Y-%m-%dT%H:%M:%S+0000").to_string()
+}
+
+/// Read a date written by the API, e.g. "2025-12-16T14:00:00.000+0000", in the given timezone
+pub fn parse_api_date(api_date: &str, timezone: &str) -> Option<DateTime<Tz>> {
+    let tz: Tz = timezone.parse().ok()?;
+    DateTime::parse_from_str(api_date, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .ok()
+        .map(|date| date.with_timezone(&tz))
+}
+
+/// Whether a date from the API falls at local midnight, i.e. it carries no time of day
+pub fn is_local_midnight(api_date: &str, timezone: &str) -> bool {
+    parse_api_date(api_date, timezone).is_some_and(|date| date.time() == NaiveTime::MIN)
+}
+
+/// Parse compact durations such as "15m", "1h30m", "2d" or "1w" into minutes
+pub fn parse_duration(input: &str) -> Option<i64> {
+    let mut total = 0;
+    let mut number = String::new();
+
+    for c in input.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let value: i64 = number.parse().ok()?;
+        number.clear();
+        total += match c {
+            'm' => value,
+            'h' => value * 60,
+            'd' => value * 60 * 24,
+            'w' => value * 60 * 24 * 7,
+            _ => return None,
+        };
+    }
+
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+    Some(total)
+}
+
+/// Parse times such as "9am", "2:30pm" or "14:30"
+pub fn parse_time(input: &str) -> Option<NaiveTime> {
+    let mut input = input.trim().to_lowercase().replace(' ', "");
+    match input.as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    // chrono needs the minutes, "9am" becomes "9:00am"
+    if !input.contains(':') {
+        let split = input
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(input.len());
+        input.insert_str(split, ":00");
+    }
+    ["%I:%M%p", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(&input, format).ok())
+}
+
+/// Only words that are clearly times, so "june 5" keeps its day
+fn parse_time_word(word: &str) -> Option<NaiveTime> {
+    let is_time = word.contains(':')
+        || word.ends_with("am")
+        || word.ends_with("pm")
+        || word == "noon"
+        || word == "midnight";
+    is_time.then(|| parse_time(word)).flatten()
+}
+
+/// Parse "2pm", "14:00" or a range "2pm-4pm"
+fn parse_time_range(word: &str) -> Option<(Option<NaiveTime>, NaiveTime)> {
+    match word.split_once('-') {
+        Some((start, end)) => Some((Some(parse_time_word(start)?), parse_time_word(end)?)),
+        None => Some((None, parse_time_word(word)?)),
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let weekdays = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    weekdays
+        .iter()
+        .find(|(name, _)| name.starts_with(word))
+        .map(|(_, day)| *day)
+}
+
+/// The next given weekday after `today`, one to seven days ahead
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 { 7 } else { ahead };
+    today + Duration::days(ahead as i64)
+}
+
+fn end_of_month(date: NaiveDate) -> Option<NaiveDate> {
+    let first = date.with_day(1)?;
+    Some(first.checked_add_months(Months::new(1))? - Duration::days(1))
+}
+
+/// "in 2 hours" or "in 3 days", minutes and hours are exact, larger units keep the time of day
+fn parse_in(words: &[&str], now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let (count, unit) = match words {
+        ["in", "a" | "an", unit] => (1, *unit),
+        ["in", count, unit] => (count.parse::<u32>().ok()?, *unit),
+        _ => return None,
+    };
+
+    match unit.trim_end_matches('s') {
+        "minute" | "min" => Some(now + Duration::minutes(count as i64)),
+        "hour" | "hr" => Some(now + Duration::hours(count as i64)),
+        "day" => Some(now + Duration::days(count as i64)),
+        "week" => Some(now + Duration::weeks(count as i64)),
+        "month" => now.checked_add_months(Months::new(count)),
+        "year" => now.checked_add_months(Months::new(count * 12)),
+        _ => None,
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+fn parse_month(word: &str) -> Option<u32> {
+    if word.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|name| name.starts_with(word))
+        .map(|i| i as u32 + 1)
+}
+
+/// "5", "5th" or "22nd"
+fn parse_day_of_month(word: &str) -> Option<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &word[digits.len()..];
+    if digits.is_empty() || digits.len() > 2 || !matches!(suffix, "" | "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// "12/25" or "12/25/2026", the year must have four digits
+fn parse_numeric_date(word: &str) -> Option<(Option<i32>, u32, u32)> {
+    let parts: Vec<&str> = word.split('/').collect();
+    let number = |part: &str| {
+        (!part.is_empty() && part.len() <= 2)
+            .then(|| part.parse::<u32>().ok())
+            .flatten()
+    };
+    match parts.as_slice() {
+        [month, day] => Some((None, number(month)?, number(day)?)),
+        [month, day, year] if year.len() == 4 => {
+            Some((Some(year.parse().ok()?), number(month)?, number(day)?))
+        }
+        _ => None,
+    }
+}
+
+/// Calendar dates: "june 5", "5th june", "12/25" and "12/25/2026".
+/// Without a year the date is the next one to come, "june 5" in October is next June.
+fn parse_calendar_date(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    let (year, month, day) = match words {
+        [month, day] | [day, month] if parse_month(month).is_some() => {
+            (None, parse_month(month)?, parse_day_of_month(day)?)
+        }
+        [date] => parse_numeric_date(date)?,
+        _ => return None,
+    };
+
+    match year {
+        Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+        // Feb 29 may only exist in a later year
+        None => (today.year()..=today.year() + 4)
+            .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+            .find(|date| *date >= today),
+    }
+}
+
+fn parse_day(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    match words {
+        [] | ["today"] | ["tonight"] => Some(today),
+        ["tomorrow"] => Some(today + Duration::days(1)),
+        ["next", "week"] => Some(today + Duration::weeks(1)),
+        ["next", "month"] => today.checked_add_months(Months::new(1)),
+        ["next", "year"] => today.checked_add_months(Months::new(12)),
+        ["end", "of", "week"] => {
+            let to_sunday = 6 - today.weekday().num_days_from_monday();
+            Some(today + Duration::days(to_sunday as i64))
+        }
+        ["end", "of", "month"] => end_of_month(today),
+        ["end", "of", "year"] => NaiveDate::from_ymd_opt(today.year(), 12, 31),
+        [day] | ["this" | "next" | "on", day] if parse_weekday(day).is_some() => {
+            Some(next_weekday(today, parse_weekday(day)?))
+        }
+        [day] if NaiveDate::parse_from_str(day, "%Y-%m-%d").is_ok() => {
+            NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+        }
+        _ => parse_calendar_date(words, today),
+    }
+}
+
+fn parse_iso(input: &str, tz: &Tz) -> Option<DateTime<Tz>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Some(dt.with_timezone(tz));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S%z") {
+        return Some(dt.with_timezone(tz));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .and_then(|dt| tz.from_local_datetime(&dt).earliest())
+}
+
+fn parse_natural(input: &str, tz: &Tz) -> Option<DateSpan> {
+    let now = Utc::now().with_timezone(tz);
+    let text = input.to_lowercase();
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+
+    if let Some(dt) = parse_in(&words, now) {
+        return Some(DateSpan::at(dt));
+    }
+
+    // A trailing time or range, optionally introduced by "at": "tomorrow at 3pm"
+    let mut times = None;
+    if let Some(last) = words.last() {
+        if let Some(range) = parse_time_range(last) {
+            times = Some(range);
+            words.pop();
+        } else if let [.., hour, meridiem @ ("am" | "pm")] = words.as_slice() {
+            times = Some((None, parse_time_word(&format!("{}{}", hour, meridiem))?));
+            words.truncate(words.len() - 2);
+        }
+        if times.is_some() && words.last() == Some(&"at") {
+            words.pop();
+        }
+    }
+
+    if words.is_empty() && times.is_none() {
+        return None;
+    }
+
+    let date = parse_day(&words, now.date_naive())?;
+    let (start, end) = times.unwrap_or((None, NaiveTime::MIN));
+
+    let end_dt = date.and_time(end);
+    let start_dt = start.map(|start| date.and_time(start));
+
+    // "11pm-1am" ends the next day
+    let end_dt = match start_dt {
+        Some(start_dt) if end_dt <= start_dt => end_dt + Duration::days(1),
+        _ => end_dt,
+    };
+
+    // Convert from local timezone
+    let start = match start_dt {
+        Some(start_dt) => Some(tz.from_local_datetime(&start_dt).earliest()?),
+        None => None,
+    };
+    let end = tz.from_local_datetime(&end_dt).earliest()?;
+    Some(DateSpan { start, end })
+}
+
+/// Parse a user supplied date in the given timezone.
+/// Dates without a time fall at local midnight.
+pub fn parse_flexible_date(input: &str, timezone: &str) -> Result<DateSpan> {
+    // Parse the timezone
+    let tz: Tz = timezone.parse().map_err(|_| {
+        Error::Config(format!(
+            "Cannot use timezone '{}', check your system timezone settings",
+            timezone
+        ))
+    })?;
+    let input = input.trim();
+
+    if let Some(dt) = parse_iso(input, &tz) {
+        return Ok(DateSpan::at(dt));
+    }
+
+    parse_natural(input, &tz).ok_or_else(|| {
+        Error::Validation(format!(
+            "Cannot understand date '{}'.\n{}",
+            input, ACCEPTED_FORMATS
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn today_utc() -> NaiveDate {
+        Utc::now().date_naive()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("15m"), Some(15));
+        assert_eq!(parse_duration("1h30m"), Some(90));
+        assert_eq!(parse_duration("2d"), Some(2 * 24 * 60));
+        assert_eq!(parse_duration("1w"), Some(7 * 24 * 60));
+        assert_eq!(parse_duration("15"), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("3x"), None);
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!(parse_time("9am"), NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(parse_time("2:30pm"), NaiveTime::from_hms_opt(14, 30, 0));
+        assert_eq!(parse_time("14:30"), NaiveTime::from_hms_opt(14, 30, 0));
+        assert_eq!(parse_time("noon"), NaiveTime::from_hms_opt(12, 0, 0));
+        assert_eq!(parse_time("25:00"), None);
+    }
+
+    #[test]
+    fn parses_relative_days() {
+        // A Wednesday
+        let today = date(2026, 10, 14);
+        assert_eq!(parse_day(&["today"], today), Some(today));
+        assert_eq!(parse_day(&["tomorrow"], today), Some(date(2026, 10, 15)));
+        assert_eq!(parse_day(&["friday"], today), Some(date(2026, 10, 16)));
+        assert_eq!(parse_day(&["next", "wed"], today), Some(date(2026, 10, 21)));
+        assert_eq!(
+            parse_day(&["end", "of", "week"], today),
+            Some(date(2026, 10, 18))
+        );
+        assert_eq!(
+            parse_day(&["end", "of", "month"], today),
+            Some(date(2026, 10, 31))
+        );
+        assert_eq!(parse_day(&["2026-12-25"], today), Some(date(2026, 12, 25)));
+    }
+
+    #[test]
+    fn parses_calendar_dates() {
+        let today = date(2026, 10, 14);
+        assert_eq!(
+            parse_day(&["december", "5"], today),
+            Some(date(2026, 12, 5))
+        );
+        assert_eq!(parse_day(&["5th", "dec"], today), Some(date(2026, 12, 5)));
+        assert_eq!(parse_day(&["oct", "14th"], today), Some(today));
+        assert_eq!(parse_day(&["12/25"], today), Some(date(2026, 12, 25)));
+        assert_eq!(parse_day(&["12/25/2027"], today), Some(date(2027, 12, 25)));
+        assert_eq!(parse_day(&["1/5/2020"], today), Some(date(2020, 1, 5)));
+    }
+
+    #[test]
+    fn moves_past_calendar_dates_to_next_year() {
+        let today = date(2026, 10, 18);
+        assert_eq!(parse_day(&["june", "5"], today), Some(date(2027, 6, 5)));
+        assert_eq!(parse_day(&["sep", "22nd"], today), Some(date(2027, 9, 22)));
+        assert_eq!(parse_day(&["10/17"], today), Some(date(2027, 10, 17)));
+        assert_eq!(parse_day(&["feb", "29"], today), Some(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn rejects_impossible_calendar_dates() {
+        let today = date(2026, 10, 14);
+        assert_eq!(parse_day(&["feb", "30"], today), None);
+        assert_eq!(parse_day(&["2/29/2027"], today), None);
+        assert_eq!(parse_day(&["june", "0"], today), None);
+        assert_eq!(parse_day(&["13/40"], today), None);
+        assert_eq!(parse_day(&["2/30/2028"], today), None);
+        assert_eq!(parse_day(&["12/25/27"], today), None);
+        assert_eq!(parse_day(&["june", "5x"], today), None);
+    }
+
+    #[test]
+    fn rejects_leftover_words() {
+        for input in [
+            "tomorrow blah",
+            "friday please",
+            "june 5 about the trip",
+            "call mom tomorrow",
+            "tomorrow 5",
+            "banana",
+        ] {
+            let error = parse_flexible_date(input, "UTC").err();
+            assert!(
+                matches!(&error, Some(Error::Validation(message)) if message.contains(ACCEPTED_FORMATS)),
+                "'{}' should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parses_dates_with_times() {
+        let tomorrow = today_utc() + Duration::days(1);
+
+        let span = parse_flexible_date("tomorrow 3pm", "UTC").unwrap();
+        assert!(span.start.is_none());
+        assert_eq!(
+            span.end.naive_local(),
+            tomorrow.and_hms_opt(15, 0, 0).unwrap()
+        );
+
+        let span = parse_flexible_date("Tomorrow at 5 pm", "UTC").unwrap();
+        assert_eq!(
+            span.end.naive_local(),
+            tomorrow.and_hms_opt(17, 0, 0).unwrap()
+        );
+
+        let span = parse_flexible_date("tomorrow", "UTC").unwrap();
+        assert_eq!(span.end.naive_local(), tomorrow.and_time(NaiveTime::MIN));
+    }
+
+    #[test]
+    fn parses_time_ranges() {
+        let span = parse_flexible_date("2025-12-16 2pm-4pm", "UTC").unwrap();
+        let day = date(2025, 12, 16);
+        assert_eq!(
+            span.start.unwrap().naive_local(),
+            day.and_hms_opt(14, 0, 0).unwrap()
+        );
+        assert_eq!(span.end.naive_local(), day.and_hms_opt(16, 0, 0).unwrap());
+
+        // Ranges past midnight end the next day
+        let span = parse_flexible_date("2025-12-16 11pm-1am", "UTC").unwrap();
+        assert_eq!(
+            span.end.naive_local(),
+            date(2025, 12, 17).and_hms_opt(1, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_iso_dates_in_the_timezone() {
+        let span = parse_flexible_date("2025-12-16T14:00:00+02:00", "Europe/Berlin").unwrap();
+        assert_eq!(
+            span.end.naive_local(),
+            date(2025, 12, 16).and_hms_opt(13, 0, 0).unwrap()
+        );
+        assert_eq!(to_api_date(&span.end), "2025-12-16T12:00:00+0000");
+    }
+
+    #[test]
+    fn detects_dates_without_a_time_of_day() {
+        assert!(is_local_midnight("2025-12-16T00:00:00+0000", "UTC"));
+        assert!(is_local_midnight(
+            "2025-12-15T23:00:00.000+0000",
+            "Europe/Berlin"
+        ));
+        assert!(!is_local_midnight(
+            "2025-12-16T00:00:00.000+0000",
+            "Europe/Berlin"
+        ));
+        assert!(!is_local_midnight("2025-12-16T14:00:00+0000", "UTC"));
+        assert!(!is_local_midnight("not a date", "UTC"));
+    }
+
+    #[test]
+    fn rejects_unknown_timezones() {
+        assert!(matches!(
+            parse_flexible_date("tomorrow", "Mars/Olympus"),
+            Err(Error::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use std::{env, fs, process::Command};
+
+use crate::error::{Error, Result};
+
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+fn editor_command() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or(String::from(if cfg!(windows) { "notepad" } else { "vi" }))
+}
+
+/// Open the user's editor on a temporary file pre-filled with `initial`
+/// and return what was written above the scissors line
+pub fn edit(initial: &str) -> Result<String> {
+    let path = env::temp_dir().join(format!("tick-{}.md", uuid::Uuid::new_v4()));
+
+    let template = format!(
+        "{}\n\n{}\n# Write the task description above this line.\n# Everything below it is ignored.\n",
+        initial, SCISSORS
+    );
+    fs::write(&path, template)?;
+
+    // $EDITOR may carry arguments, e.g. "code --wait"
+    let command = editor_command();
+    let mut parts = command.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::Config(String::from("No editor configured, set $EDITOR")))?;
+
+    let status = Command::new(program).args(parts).arg(&path).status();
+    let written = fs::read_to_string(&path);
+    fs::remove_file(&path).ok();
+
+    let status =
+        status.map_err(|e| Error::Io(format!("Cannot run editor '{}': {}", command, e)))?;
+    if !status.success() {
+        return Err(Error::Io(format!(
+            "Editor '{}' exited with {}",
+            command, status
+        )));
+    }
+
+    let written = written?;
+    let content = written
+        .split(SCISSORS)
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    Ok(content)
+}
@@ -0,0 +1,135 @@
+use std::{fmt, io};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Every failure the CLI reports, each kind maps to its own exit code so scripts can react
+#[derive(Debug)]
+pub enum Error {
+    /// Bad user input: dates, flags, ambiguous names, rejected request bodies
+    Validation(String),
+    /// Missing or unreadable configuration
+    Config(String),
+    /// Not logged in or the login flow failed
+    Auth(String),
+    /// The API rejected the stored token
+    AuthExpired,
+    NotFound(String),
+    RateLimited {
+        retry_after: Option<u64>,
+    },
+    /// The API couldn't be reached
+    Network(String),
+    /// Any other unsuccessful or unreadable API response
+    Api {
+        status: u16,
+        message: String,
+    },
+    /// Some items of a batch failed, the others went through
+    PartialFailure {
+        failed: usize,
+        total: usize,
+    },
+    /// Local failures: terminal, editor, files, stdin
+    Io(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Io(_) => 1,
+            Error::Validation(_) => 2,
+            Error::Config(_) => 3,
+            Error::Auth(_) => 4,
+            Error::AuthExpired => 5,
+            Error::NotFound(_) => 6,
+            Error::RateLimited { .. } => 7,
+            Error::Network(_) => 8,
+            Error::Api { .. } => 9,
+            Error::PartialFailure { .. } => 10,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Validation(message) | Error::Config(message) | Error::NotFound(message) => {
+                write!(f, "{}", message)
+            }
+            Error::Auth(message) => write!(f, "Authentication failed: {}", message),
+            Error::AuthExpired => write!(f, "Your session has expired. Run: tick auth login"),
+            Error::RateLimited {
+                retry_after: Some(seconds),
+            } => write!(f, "Too many requests, try again in {} seconds", seconds),
+            Error::RateLimited { retry_after: None } => {
+                write!(f, "Too many requests, try again later")
+            }
+            Error::Network(message) => write!(f, "Cannot reach TickTick: {}", message),
+            Error::Api { status, message } => {
+                write!(f, "TickTick API error ({}): {}", status, message)
+            }
+            Error::PartialFailure { failed, total } => {
+                write!(f, "{} of {} task(s) failed", failed, total)
+            }
+            Error::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            return Error::Api {
+                status: e.status().map_or(0, |s| s.as_u16()),
+                message: format!("unexpected response: {}", e),
+            };
+        }
+        Error::Network(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Api {
+            status: 0,
+            message: format!("unexpected response: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+impl From<dialoguer::Error> for Error {
+    fn from(e: dialoguer::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+impl From<confy::ConfyError> for Error {
+    fn from(e: confy::ConfyError) -> Self {
+        Error::Config(format!("Cannot read the config file: {}", e))
+    }
+}
+
+impl From<keyring::Error> for Error {
+    fn from(e: keyring::Error) -> Self {
+        match e {
+            keyring::Error::NoEntry => {
+                Error::Auth(String::from("no saved token. Run: tick auth login"))
+            }
+            e => Error::Config(format!("Cannot access the system keychain: {}", e)),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for Error {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Error::Io(e.to_string())
+    }
+}
@@ -1,17 +1,56 @@
+use chrono::Utc;
 use keyring::Entry;
-use std::error::Error;
+use serde::{Deserialize, Serialize};
 
-use crate::config;
+use crate::{
+    config,
+    error::{Error, Result},
+};
 
 pub struct CredentialStore;
 
-impl CredentialStore {
-    pub fn save(user: &str, token: &str) -> Result<(), Box<dyn Error>> {
-        let app_name = &config::get().app_name;
+/// A TickTick developer app, used by the direct login
+#[derive(Serialize, Deserialize)]
+pub struct ClientCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+}
 
-        let entry = Entry::new(app_name, user)?;
+fn client_entry(user: &str) -> Result<Entry> {
+    Ok(Entry::new(
+        &config::get().app_name,
+        &format!("{}:client", user),
+    )?)
+}
 
-        match entry.set_password(token) {
+/// What the keychain keeps for a user.
+/// Tokens saved by older versions are a bare access token without expiry.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix timestamp in seconds, `None` when the server didn't say
+    pub expires_at: Option<i64>,
+    pub saved_at: Option<i64>,
+}
+
+impl Token {
+    /// Expired already, or expiring within `margin` seconds
+    pub fn expires_within(&self, margin: i64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - margin <= Utc::now().timestamp())
+    }
+}
+
+fn token_entry(user: &str) -> Result<Entry> {
+    Ok(Entry::new(&config::get().app_name, user)?)
+}
+
+impl CredentialStore {
+    pub fn save(user: &str, token: &Token) -> Result<()> {
+        let entry = token_entry(user)?;
+
+        match entry.set_password(&serde_json::to_string(token)?) {
             Ok(_) => println!("✅ Auth set"),
             Err(e) => {
                 println!("❌ Failed to set Auth");
@@ -27,10 +66,46 @@ impl CredentialStore {
 
         Ok(())
     }
-    pub fn get(user: &str) -> Result<String, Box<dyn Error>> {
-        let app_name = &config::get().app_name;
-        let entry = Entry::new(app_name, user)?;
-        let token = entry.get_password()?;
+
+    /// Replace the token without any output, used when it is refreshed in the background
+    pub fn update(user: &str, token: &Token) -> Result<()> {
+        token_entry(user)?.set_password(&serde_json::to_string(token)?)?;
+        Ok(())
+    }
+
+    pub fn get(user: &str) -> Result<Token> {
+        let saved = token_entry(user)?.get_password()?;
+        let token = serde_json::from_str(&saved).unwrap_or(Token {
+            access_token: saved,
+            refresh_token: None,
+            expires_at: None,
+            saved_at: None,
+        });
         Ok(token)
     }
+
+    /// Remove the token of `user`, `false` when there was none
+    pub fn delete(user: &str) -> Result<bool> {
+        match token_entry(user)?.delete_password() {
+            Ok(_) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save_client(user: &str, client: &ClientCredentials) -> Result<()> {
+        client_entry(user)?.set_password(&serde_json::to_string(client)?)?;
+        Ok(())
+    }
+
+    /// The developer app saved for `user`, `None` when there is none yet
+    pub fn get_client(user: &str) -> Result<Option<ClientCredentials>> {
+        match client_entry(user)?.get_password() {
+            Ok(saved) => serde_json::from_str(&saved)
+                .map(Some)
+                .map_err(|e| Error::Config(format!("Cannot read the saved developer app: {}", e))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
@@ -1,57 +1,408 @@
-use clap::{Args, Parser, Subcommand};
+use std::process::ExitCode;
 
-use crate::{keychain::CredentialStore, tick_tick_api::TaskPriority};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+
+use crate::{
+    config::AuthMode,
+    error::Result,
+    tick_tick_api::{ProjectKind, TaskPriority, ViewMode},
+};
 
 mod auth;
+mod callback;
 mod client;
 mod config;
+mod dates;
+mod editor;
+mod error;
 mod keychain;
+mod oauth;
+mod quick_add;
+mod recurrence;
+mod reminder;
 mod services;
 mod tick_tick_api;
 mod ui;
 
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
+struct ContentArgs {
+    /// Task description
+    #[arg(short, long, group = "description")]
+    content: Option<String>,
+
+    /// Read the task description from a file
+    #[arg(long, group = "description")]
+    content_file: Option<std::path::PathBuf>,
+
+    /// Write the task description in $EDITOR
+    #[arg(long, group = "description")]
+    edit: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct ScheduleArgs {
+    /// Due Date, e.g. "2025-12-25", "2025-12-16 2pm-4pm", "tomorrow 3pm", "next friday", "in 2 hours"
+    #[arg(short, long)]
+    due: Option<String>,
+
+    /// Start Date (same formats as --due)
+    #[arg(long)]
+    start: Option<String>,
+
+    /// Duration from the start date, e.g. 45m or 1h30m
+    #[arg(long)]
+    duration: Option<String>,
+
+    /// Mark the task as an all-day task, `--all-day=false` turns it off again
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    all_day: Option<bool>,
+}
+
+#[derive(Args, Debug, Default)]
 struct CreateArgs {
     /// Task description (positional argument)
     title: String,
 
-    /// Project Name
+    /// Project Name, defaults to the configured project
     #[arg(long)]
-    project: String,
+    project: Option<String>,
 
     /// Tags: use comma separated
     #[arg(short, long, value_delimiter = ',')]
     tags: Vec<String>,
 
-    /// Due Date (format: YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+    #[command(flatten)]
+    schedule: ScheduleArgs,
+
+    //Priority
     #[arg(short, long)]
-    due: Option<String>,
+    priority: Option<TaskPriority>,
+
+    /// Checklist item, repeat the flag for several items
+    #[arg(short, long = "item")]
+    items: Vec<String>,
+
+    /// Recurrence, e.g. "every weekday", "every 2 weeks on mon,thu" or an RRULE
+    #[arg(short, long)]
+    repeat: Option<String>,
+
+    /// Reminder, e.g. "15m before", "1d before" or "at 9:00am", repeat the flag for several
+    #[arg(long)]
+    remind: Vec<String>,
+
+    /// Kanban column name or id
+    #[arg(long)]
+    column: Option<String>,
+
+    #[command(flatten)]
+    content: ContentArgs,
+}
+
+#[derive(Args, Debug)]
+struct AddArgs {
+    /// Task text with inline #tags, ^project, !priority and @due date
+    #[arg(required = true)]
+    text: Vec<String>,
+
+    /// Use the text as the title without looking for inline fields
+    #[arg(long)]
+    no_parse: bool,
+
+    /// Print the parsed fields without creating the task
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+struct ShowArgs {
+    /// Task id or title (positional argument)
+    task: String,
+
+    /// Project Name where the task lives
+    #[arg(long)]
+    project: Option<String>,
+
+    /// Print the raw task as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct UpdateArgs {
+    /// Task id or title (positional argument)
+    task: String,
+
+    /// Project Name where the task lives
+    #[arg(long)]
+    project: Option<String>,
+
+    /// New title
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Tags: use comma separated, replaces the current tags
+    #[arg(short, long, value_delimiter = ',')]
+    tags: Option<Vec<String>>,
+
+    #[command(flatten)]
+    schedule: ScheduleArgs,
 
     //Priority
     #[arg(short, long)]
     priority: Option<TaskPriority>,
+
+    /// Move the task to another project
+    #[arg(long)]
+    to: Option<String>,
+
+    /// Recurrence, e.g. "every weekday" or an RRULE, use "none" to remove it
+    #[arg(short, long)]
+    repeat: Option<String>,
+
+    /// Reminder, replaces the current ones, use "none" to remove them
+    #[arg(long)]
+    remind: Vec<String>,
+
+    /// Kanban column name or id
+    #[arg(long)]
+    column: Option<String>,
+
+    #[command(flatten)]
+    content: ContentArgs,
 }
 
 #[derive(Args, Debug)]
-struct GetArgs {
+#[command(group(ArgGroup::new("destination").required(true).multiple(true).args(["to", "column"])))]
+struct MoveArgs {
+    /// Task ids or titles (positional arguments)
+    #[arg(required_unless_present = "tag")]
+    tasks: Vec<String>,
+
+    /// Project Name where the tasks live
+    #[arg(long)]
+    project: Option<String>,
+
+    /// Move every task with this tag from the source project
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Destination Project Name
+    #[arg(long)]
+    to: Option<String>,
+
+    /// Kanban column name or id (in the destination project)
+    #[arg(long)]
+    column: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct StatusArgs {
+    /// Task ids or titles (positional arguments)
+    #[arg(required = true)]
+    tasks: Vec<String>,
+
+    /// Project Name where the tasks live
+    #[arg(long)]
+    project: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ReopenArgs {
+    /// Task ids (positional arguments), completed tasks can't be looked up by title
+    #[arg(required = true)]
+    tasks: Vec<String>,
+
+    /// Project Name where the tasks live
+    #[arg(long)]
+    project: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct DeleteArgs {
+    /// Task ids or titles, use `-` to read them from stdin (one per line)
+    #[arg(required = true)]
+    tasks: Vec<String>,
+
+    /// Project Name where the tasks live
+    #[arg(long)]
+    project: Option<String>,
+
+    /// Skip the confirmation prompt
     #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(Args, Debug)]
+struct ItemArgs {
+    /// Task id or title (positional argument)
+    task: String,
+
+    /// Checklist item: title when adding, otherwise id, number or title
+    item: String,
+
+    /// Project Name where the task lives
+    #[arg(long)]
+    project: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum ItemCommands {
+    /// Add a checklist item to a task
+    Add(ItemArgs),
+    /// Mark a checklist item as done
+    Check(ItemArgs),
+    /// Mark a checklist item as not done
+    Uncheck(ItemArgs),
+    /// Remove a checklist item from a task
+    Remove(ItemArgs),
+}
+
+#[derive(Args, Debug)]
+struct GetArgs {
+    /// Show tasks from inbox and every project
+    #[arg(short, long, conflicts_with = "project")]
     all: bool,
 
+    /// Project Name
     #[arg(short, long)]
     project: Option<String>,
+
+    /// Show kanban projects as a table instead of a board
+    #[arg(long)]
+    table: bool,
+
+    /// Print only the task ids, one per line, e.g. to pipe into `tick task delete -`
+    #[arg(long)]
+    ids: bool,
 }
 
 #[derive(Subcommand)]
 enum TaskCommands {
     Create(CreateArgs),
     Get(GetArgs),
+    Show(ShowArgs),
+    Update(UpdateArgs),
+    Complete(StatusArgs),
+    Reopen(ReopenArgs),
+    Delete(DeleteArgs),
+    Move(MoveArgs),
+    Item {
+        #[command(subcommand)]
+        action: ItemCommands,
+    },
+}
+
+#[derive(Args, Debug)]
+struct ProjectListArgs {
+    /// Include archived projects
+    #[arg(short, long)]
+    all: bool,
+
+    /// Print the projects as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args, Debug)]
+struct ProjectCreateArgs {
+    /// Project Name (positional argument)
+    name: String,
+
+    /// Color as hex, e.g. #F18181
+    #[arg(short, long)]
+    color: Option<String>,
+
+    /// View mode
+    #[arg(short, long)]
+    view: Option<ViewMode>,
+
+    /// Project kind
+    #[arg(short, long)]
+    kind: Option<ProjectKind>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectRenameArgs {
+    /// Current Project Name
+    project: String,
+
+    /// New Project Name
+    name: String,
+}
+
+#[derive(Args, Debug)]
+struct ProjectColumnsArgs {
+    /// Project Name
+    project: String,
+}
+
+#[derive(Args, Debug)]
+struct ProjectArchiveArgs {
+    /// Project Name
+    project: String,
+
+    /// Move the project back out of the archive
+    #[arg(long)]
+    restore: bool,
+}
+
+#[derive(Args, Debug)]
+struct ProjectDeleteArgs {
+    /// Project Name
+    project: String,
+
+    /// Skip the confirmation prompt
+    #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(Subcommand)]
+enum ProjectCommands {
+    /// List projects
+    List(ProjectListArgs),
+    /// Create a project
+    Create(ProjectCreateArgs),
+    /// Rename a project
+    Rename(ProjectRenameArgs),
+    /// List the kanban columns of a project
+    Columns(ProjectColumnsArgs),
+    /// Archive (or restore) a project
+    Archive(ProjectArchiveArgs),
+    /// Delete a project and its tasks
+    Delete(ProjectDeleteArgs),
+}
+
+#[derive(Args, Debug)]
+struct AuthLoginArgs {
+    /// Don't open a browser: print the login URL and paste back the redirect URL or code
+    #[arg(long)]
+    headless: bool,
+}
+
+#[derive(Args, Debug)]
+struct AuthLogoutArgs {
+    /// Also ask the server to invalidate the token
+    #[arg(long)]
+    revoke: bool,
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Log in and save the token in the system keychain
+    Login(AuthLoginArgs),
+    /// Show the configured email, the saved token and whether the API accepts it
+    Status,
+    /// Delete the saved token
+    Logout(AuthLogoutArgs),
+    /// Enter the client id and secret of your TickTick developer app (direct login)
+    Client,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Auth {
-        #[arg(short, long)]
-        login: bool,
+        #[command(subcommand)]
+        action: AuthCommands,
     },
     Config {
         #[arg(short, long)]
@@ -60,13 +411,23 @@ enum Commands {
         #[arg(short, long)]
         project: Option<String>,
 
+        /// How to log in: through the AUTH_HOST proxy or directly with your own developer app
+        #[arg(long, value_enum)]
+        auth_mode: Option<AuthMode>,
+
         #[arg(short, long)]
         show: bool,
     },
     Task {
         #[command(subcommand)]
-        action: TaskCommands,
+        action: Box<TaskCommands>,
     },
+    Project {
+        #[command(subcommand)]
+        action: ProjectCommands,
+    },
+    /// Quick add: tick add "Ship release notes #docs ^Work !high @tomorrow 5pm"
+    Add(AddArgs),
 }
 
 #[derive(Parser)]
@@ -77,35 +438,53 @@ struct Cli {
     command: Commands,
 }
 
-/// Initialize the API client with credentials from keychain
+/// Initialize the API client with credentials from keychain, refreshing the token when it expires soon
 /// Only call this when you need to make authenticated API calls
-fn init_authenticated_client() -> Result<(), Box<dyn std::error::Error>> {
+async fn init_authenticated_client() -> Result<()> {
     let cfg = config::AppConfig::load()?;
 
-    let email = cfg
-        .email
-        .ok_or("No email configured. Run: tick config --email <your-email>")?;
-    let token = CredentialStore::get(&email)?;
+    let email = cfg.require_email()?;
+    let token = auth::access_token(email, &cfg.oauth).await?;
 
-    client::init_client(&token)?;
+    client::init_client(&token, &cfg.retry)?;
     Ok(())
 }
 
 /// Main entry point
 /// The #[tokio::main] macro sets up async runtime
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Auth { login } => {
-            if login {
-                auth::authenticate(config::AppConfig::load()?.email).await?;
+        Commands::Auth { action } => {
+            let cfg = config::AppConfig::load()?;
+
+            match action {
+                AuthCommands::Login(args) => {
+                    auth::authenticate(&cfg, args.headless).await?;
+                }
+                AuthCommands::Status => auth::status(&cfg).await?,
+                AuthCommands::Logout(args) => auth::logout(&cfg, args.revoke).await?,
+                AuthCommands::Client => {
+                    oauth::register_client(cfg.require_email()?)?;
+                }
             }
         }
         Commands::Config {
             email,
             project,
+            auth_mode,
             show,
         } => {
             if let Some(email) = email {
@@ -116,22 +495,99 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             } else if let Some(project) = project {
                 config::AppConfig::update(|cfg| cfg.default_project = project.clone())?;
                 println!("✅ Default project saved: {}", project);
+            } else if let Some(mode) = auth_mode {
+                config::AppConfig::update(|cfg| cfg.oauth.mode = mode)?;
+                println!("✅ Auth mode saved: {:?}", mode);
             } else if show {
                 println!("{:#?}", config::AppConfig::load()?);
             }
         }
+        Commands::Project { action } => {
+            init_authenticated_client().await?;
+
+            match action {
+                ProjectCommands::List(args) => {
+                    services::projects::list_projects(&args).await?;
+                }
+                ProjectCommands::Create(args) => {
+                    let project = services::projects::create_project(&args).await?;
+                    println!("✅ Project created: {} ({})", project.name, project.id)
+                }
+                ProjectCommands::Rename(args) => {
+                    let project = services::projects::rename_project(&args).await?;
+                    println!("✅ Project renamed: {}", project.name)
+                }
+                ProjectCommands::Columns(args) => {
+                    services::projects::list_columns(&args).await?;
+                }
+                ProjectCommands::Archive(args) => {
+                    let project = services::projects::archive_project(&args).await?;
+                    if args.restore {
+                        println!("✅ Project restored: {}", project.name)
+                    } else {
+                        println!("✅ Project archived: {}", project.name)
+                    }
+                }
+                ProjectCommands::Delete(args) => {
+                    services::projects::delete_project(&args).await?;
+                }
+            }
+        }
+        Commands::Add(args) => {
+            if !args.dry_run {
+                init_authenticated_client().await?;
+            }
+            if services::tasks::add_task(&args).await? {
+                println!("✅ Task added successfully")
+            }
+        }
         Commands::Task { action } => {
             // Only initialize client when making API calls
-            init_authenticated_client()?;
+            init_authenticated_client().await?;
 
-            match action {
+            match *action {
                 TaskCommands::Create(args) => {
                     services::tasks::create_task(&args).await?;
                     println!("✅ Task added successfully")
                 }
                 TaskCommands::Get(args) => {
-                    println!("{:?}", args);
-                    services::tasks::get_tasks(args.project).await?;
+                    services::tasks::get_tasks(&args).await?;
+                }
+                TaskCommands::Show(args) => {
+                    services::tasks::show_task(&args).await?;
+                }
+                TaskCommands::Update(args) => {
+                    let task = services::tasks::update_task(&args).await?;
+                    println!("✅ Task updated: {}", task.title)
+                }
+                TaskCommands::Complete(args) => {
+                    services::tasks::complete_tasks(&args).await?;
+                }
+                TaskCommands::Reopen(args) => {
+                    services::tasks::reopen_tasks(&args).await?;
+                }
+                TaskCommands::Delete(args) => {
+                    services::tasks::delete_tasks(&args).await?;
+                }
+                TaskCommands::Move(args) => {
+                    services::tasks::move_tasks(&args).await?;
+                }
+                TaskCommands::Item { action } => {
+                    let task = match action {
+                        ItemCommands::Add(args) => services::items::add_item(&args).await?,
+                        ItemCommands::Check(args) => {
+                            services::items::set_item_status(&args, true).await?
+                        }
+                        ItemCommands::Uncheck(args) => {
+                            services::items::set_item_status(&args, false).await?
+                        }
+                        ItemCommands::Remove(args) => services::items::remove_item(&args).await?,
+                    };
+                    let (checked, total) = task.item_progress().unwrap_or((0, 0));
+                    println!(
+                        "✅ Checklist updated: {} ({}/{})",
+                        task.title, checked, total
+                    )
                 }
             }
         }
@@ -0,0 +1,232 @@
+//! Authorization code flow with PKCE straight against TickTick's OAuth endpoints,
+//! for users who registered their own developer app instead of going through the proxy.
+
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use dialoguer::{Input, Password};
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    callback::{self, CallbackServer, CALLBACK_TIMEOUT},
+    config,
+    error::{Error, Result},
+    keychain::{ClientCredentials, CredentialStore, Token},
+};
+
+const SCOPE: &str = "tasks:read tasks:write";
+
+/// Code verifier and its S256 challenge (RFC 7636)
+struct Pkce {
+    verifier: String,
+    challenge: String,
+}
+
+impl Pkce {
+    fn new() -> Self {
+        // Two v4 uuids give 244 random bits, all within the allowed characters
+        let verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Pkce {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Lifetime in seconds
+    pub expires_in: Option<i64>,
+}
+
+impl TokenResponse {
+    /// The token to keep, a refresh response without a new refresh token keeps `previous`
+    pub fn into_token(self, previous_refresh_token: Option<String>) -> Token {
+        let now = Utc::now().timestamp();
+        Token {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token.or(previous_refresh_token),
+            expires_at: self.expires_in.map(|seconds| now + seconds),
+            saved_at: Some(now),
+        }
+    }
+}
+
+fn authorize_url(client_id: &str, redirect_uri: &str, state: &str, pkce: &Pkce) -> Result<String> {
+    let oauth_host = &config::get().oauth_host;
+    let mut url = Url::parse(&format!("{}/oauth/authorize", oauth_host))
+        .map_err(|e| Error::Config(format!("Invalid OAUTH_HOST '{}': {}", oauth_host, e)))?;
+
+    url.query_pairs_mut()
+        .append_pair("client_id", client_id)
+        .append_pair("response_type", "code")
+        .append_pair("scope", SCOPE)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("state", state)
+        .append_pair("code_challenge", &pkce.challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.into())
+}
+
+async fn request_token(client: &ClientCredentials, form: &[(&str, &str)]) -> Result<TokenResponse> {
+    let res = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?
+        .post(format!("{}/oauth/token", &config::get().oauth_host))
+        .basic_auth(&client.client_id, Some(&client.client_secret))
+        .form(form)
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error_text = res.text().await?;
+        return Err(Error::Auth(format!(
+            "token exchange failed: {}",
+            error_text
+        )));
+    }
+
+    let token_response: TokenResponse = res.json().await?;
+    Ok(token_response)
+}
+
+async fn exchange_code(
+    client: &ClientCredentials,
+    code: &str,
+    redirect_uri: &str,
+    pkce: &Pkce,
+) -> Result<TokenResponse> {
+    request_token(
+        client,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", &pkce.verifier),
+            ("scope", SCOPE),
+        ],
+    )
+    .await
+}
+
+pub async fn refresh_token(
+    client: &ClientCredentials,
+    refresh_token: &str,
+) -> Result<TokenResponse> {
+    request_token(
+        client,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("scope", SCOPE),
+        ],
+    )
+    .await
+}
+
+/// Ask TickTick to invalidate a token (RFC 7009)
+pub async fn revoke_token(client: &ClientCredentials, token: &str) -> Result<()> {
+    let res = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?
+        .post(format!("{}/oauth/revoke", &config::get().oauth_host))
+        .basic_auth(&client.client_id, Some(&client.client_secret))
+        .form(&[("token", token)])
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error_text = res.text().await?;
+        return Err(Error::Auth(format!(
+            "token revocation failed: {}",
+            error_text
+        )));
+    }
+    Ok(())
+}
+
+/// Ask for the developer app credentials and keep them in the keychain
+pub fn register_client(user: &str) -> Result<ClientCredentials> {
+    println!("Register an app at https://developer.ticktick.com/manage and paste its credentials");
+
+    let client = ClientCredentials {
+        client_id: Input::new().with_prompt("Client ID").interact_text()?,
+        client_secret: Password::new().with_prompt("Client secret").interact()?,
+    };
+    CredentialStore::save_client(user, &client)?;
+
+    println!("✅ Developer app saved");
+    Ok(client)
+}
+
+/// Print the authorization URL and read back the redirect the browser landed on,
+/// for machines that can't open a browser or receive the callback
+fn paste_code(url: &str, state: &str) -> Result<String> {
+    println!("Open this URL in a browser on any machine:\n\n  {}\n", url);
+    println!("After authorizing, the browser lands on a 127.0.0.1 page that may fail to load.");
+    println!("Paste that page's URL from the address bar (or only the code):");
+
+    callback::read_pasted_code(Some(state))?
+        .ok_or_else(|| Error::Auth(String::from("no code was pasted")))
+}
+
+/// Log in with the user's own developer app and store the access token
+pub async fn login(user: &str, redirect_port: u16, headless: bool) -> Result<()> {
+    let client = match CredentialStore::get_client(user)? {
+        Some(client) => client,
+        None => register_client(user)?,
+    };
+
+    let pkce = Pkce::new();
+    let redirect_uri = callback::redirect_uri(redirect_port);
+
+    // Without a listener the redirect still lands on the port, the user pastes it instead
+    let server = if headless {
+        None
+    } else {
+        match CallbackServer::bind(redirect_port).await {
+            Ok(server) => Some(server),
+            Err(e) => {
+                eprintln!("Cannot listen for the login callback: {}", e);
+                None
+            }
+        }
+    };
+    let state = server
+        .as_ref()
+        .map_or_else(|| Uuid::new_v4().to_string(), |server| server.state.clone());
+    let url = authorize_url(&client.client_id, &redirect_uri, &state, &pkce)?;
+
+    let code = match server {
+        Some(server) if open::that(&url).is_ok() => {
+            println!("Waiting for the browser to complete the login...");
+            println!("or paste the URL it was redirected to (or the code) here:");
+            // An empty line has no code to exchange, show the full instructions then
+            match callback::wait_or_paste(server, CALLBACK_TIMEOUT).await? {
+                Some(code) => code,
+                None => paste_code(&url, &state)?,
+            }
+        }
+        Some(_) => {
+            eprintln!("Could not automatically open browser.");
+            paste_code(&url, &state)?
+        }
+        None => paste_code(&url, &state)?,
+    };
+
+    println!("Verifying token...");
+    let token = exchange_code(&client, &code, &redirect_uri, &pkce).await?;
+
+    CredentialStore::save(user, &token.into_token(None))?;
+
+    println!("✅ Auth completed");
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Quick-add syntax: pull tags, project, priority and due date out of a task title,
+//! e.g. "Ship release notes #docs ^Work !high @tomorrow 5pm".
+
+use crate::{dates::parse_flexible_date, tick_tick_api::TaskPriority};
+
+#[derive(Debug, Default)]
+pub struct QuickAdd {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub priority: Option<TaskPriority>,
+    pub due: Option<String>,
+}
+
+fn is_marker(word: &str) -> bool {
+    word.len() > 1 && word.starts_with(['#', '^', '!', '@'])
+}
+
+fn parse_priority(word: &str) -> Option<TaskPriority> {
+    match word.to_lowercase().as_str() {
+        "low" | "1" => Some(TaskPriority::Low),
+        "mid" | "medium" | "3" => Some(TaskPriority::Mid),
+        "high" | "5" => Some(TaskPriority::High),
+        _ => None,
+    }
+}
+
+/// The longest run of words after `@` that is a valid date, returns the date and the words used.
+/// Dates must use every word of the run, so trailing title words are never swallowed.
+fn parse_due(words: &[&str], timezone: &str) -> Option<(String, usize)> {
+    let first = words.first()?.strip_prefix('@')?;
+    let limit = words
+        .iter()
+        .skip(1)
+        .position(|w| is_marker(w))
+        .map_or(words.len(), |pos| pos + 1);
+
+    (1..=limit).rev().find_map(|count| {
+        let mut parts = vec![first];
+        parts.extend(&words[1..count]);
+        let text = parts.join(" ");
+        parse_flexible_date(&text, timezone)
+            .ok()
+            .map(|_| (text, count))
+    })
+}
+
+/// Split the text into a title and its inline fields.
+/// Markers that can't be understood (e.g. "@bob" or "!!") stay in the title.
+pub fn parse_quick_add(text: &str, timezone: &str) -> QuickAdd {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut parsed = QuickAdd::default();
+    let mut title: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        i += 1;
+
+        if !is_marker(word) {
+            title.push(word);
+            continue;
+        }
+
+        let (marker, value) = word.split_at(1);
+        match marker {
+            "#" => parsed.tags.push(value.to_string()),
+            "^" => parsed.project = Some(value.to_string()),
+            "!" => match parse_priority(value) {
+                Some(priority) => parsed.priority = Some(priority),
+                None => title.push(word),
+            },
+            _ => match parse_due(&words[i - 1..], timezone) {
+                Some((due, count)) => {
+                    parsed.due = Some(due);
+                    i += count - 1;
+                }
+                None => title.push(word),
+            },
+        }
+    }
+
+    parsed.title = title.join(" ");
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> QuickAdd {
+        parse_quick_add(text, "UTC")
+    }
+
+    #[test]
+    fn parses_the_readme_example() {
+        let parsed = parse("Ship release notes #docs ^Work !high @tomorrow 5pm");
+        assert_eq!(parsed.title, "Ship release notes");
+        assert_eq!(parsed.tags, vec!["docs"]);
+        assert_eq!(parsed.project.as_deref(), Some("Work"));
+        assert!(matches!(parsed.priority, Some(TaskPriority::High)));
+        assert_eq!(parsed.due.as_deref(), Some("tomorrow 5pm"));
+    }
+
+    #[test]
+    fn keeps_words_after_the_date_in_the_title() {
+        let parsed = parse("Call mom @friday about the trip");
+        assert_eq!(parsed.title, "Call mom about the trip");
+        assert_eq!(parsed.due.as_deref(), Some("friday"));
+
+        let parsed = parse("Ship notes @tomorrow 5pm please");
+        assert_eq!(parsed.title, "Ship notes please");
+        assert_eq!(parsed.due.as_deref(), Some("tomorrow 5pm"));
+
+        let parsed = parse("Book flights @june 5 for the team");
+        assert_eq!(parsed.title, "Book flights for the team");
+        assert_eq!(parsed.due.as_deref(), Some("june 5"));
+    }
+
+    #[test]
+    fn stops_the_date_at_the_next_marker() {
+        let parsed = parse("Review PR @next friday at 10am #work");
+        assert_eq!(parsed.title, "Review PR");
+        assert_eq!(parsed.tags, vec!["work"]);
+        assert_eq!(parsed.due.as_deref(), Some("next friday at 10am"));
+    }
+
+    #[test]
+    fn leaves_unknown_markers_in_the_title() {
+        let parsed = parse("Ask @bob about !! the budget !urgent");
+        assert_eq!(parsed.title, "Ask @bob about !! the budget !urgent");
+        assert!(parsed.due.is_none());
+        assert!(parsed.priority.is_none());
+    }
+}
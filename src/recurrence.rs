@@ -0,0 +1,406 @@
+//! Translate friendly repeat expressions ("every 2 weeks on mon,thu") to the
+//! RFC 5545 RRULE strings TickTick stores in `repeatFlag`, and back.
+
+use crate::error::{Error, Result};
+
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("MO", "monday"),
+    ("TU", "tuesday"),
+    ("WE", "wednesday"),
+    ("TH", "thursday"),
+    ("FR", "friday"),
+    ("SA", "saturday"),
+    ("SU", "sunday"),
+];
+
+const ORDINALS: [(&str, i32); 6] = [
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("last", -1),
+];
+
+const ACCEPTED_FORMATS: &str = "Accepted formats:
+  daily | weekly | monthly | yearly
+  every day | every 3 days | every other week
+  every weekday | every weekend | every mon,thu
+  every 2 weeks on mon,thu
+  monthly on the 15th | monthly on the last day | monthly on the last friday
+  RRULE:FREQ=WEEKLY;BYDAY=MO (raw RFC 5545)
+  none (remove the recurrence)";
+
+fn invalid(input: &str) -> Error {
+    Error::Validation(format!(
+        "Cannot understand repeat '{}'.\n{}",
+        input, ACCEPTED_FORMATS
+    ))
+}
+
+fn parse_weekday(input: &str) -> Option<&'static str> {
+    let input = input.trim();
+    if input.len() < 2 {
+        return None;
+    }
+    WEEKDAYS
+        .iter()
+        .find(|(_, name)| name.starts_with(input))
+        .map(|(code, _)| *code)
+}
+
+/// Parse "mon,thu", "mon, thu" or "mon and thu" into BYDAY codes
+fn parse_weekdays(input: &str) -> Option<Vec<&'static str>> {
+    let days: Option<Vec<&str>> = input
+        .replace(" and ", ",")
+        .split(',')
+        .map(parse_weekday)
+        .collect();
+    days.filter(|d| !d.is_empty())
+}
+
+fn parse_frequency(unit: &str) -> Option<&'static str> {
+    match unit.trim_end_matches('s') {
+        "day" => Some("DAILY"),
+        "week" => Some("WEEKLY"),
+        "month" => Some("MONTHLY"),
+        "year" => Some("YEARLY"),
+        _ => None,
+    }
+}
+
+/// Parse the "on ..." part of a monthly rule: "the 15th", "day 15", "the last day", "the last friday"
+fn parse_month_position(input: &str) -> Option<String> {
+    let input = input.trim_start_matches("the ").trim();
+
+    if input == "last day" {
+        return Some(String::from("BYMONTHDAY=-1"));
+    }
+
+    let day_number = input
+        .trim_start_matches("day ")
+        .trim_end_matches(|c: char| c.is_alphabetic());
+    if let Ok(day) = day_number.parse::<u32>() {
+        if (1..=31).contains(&day) {
+            return Some(format!("BYMONTHDAY={}", day));
+        }
+        return None;
+    }
+
+    let (ordinal, weekday) = input.split_once(' ')?;
+    let position = ORDINALS.iter().find(|(name, _)| *name == ordinal)?.1;
+    let weekday = parse_weekday(weekday)?;
+    Some(format!("BYDAY={}{}", position, weekday))
+}
+
+fn normalize_raw_rule(input: &str) -> Result<String> {
+    let rule = input.trim().to_uppercase();
+    let rule = rule.strip_prefix("RRULE:").unwrap_or(&rule);
+
+    let has_freq = rule.split(';').any(|part| {
+        matches!(
+            part.split_once('='),
+            Some(("FREQ", "DAILY" | "WEEKLY" | "MONTHLY" | "YEARLY"))
+        )
+    });
+    if !has_freq {
+        return Err(invalid(input));
+    }
+    Ok(format!("RRULE:{}", rule))
+}
+
+/// Parse a repeat expression into a `repeatFlag` value.
+/// Returns `None` when the recurrence should be removed ("none").
+pub fn parse_repeat(input: &str) -> Result<Option<String>> {
+    let text = input.trim().to_lowercase();
+
+    if matches!(text.as_str(), "none" | "never" | "off") {
+        return Ok(None);
+    }
+
+    if text.starts_with("rrule:") || text.starts_with("freq=") {
+        return normalize_raw_rule(input).map(Some);
+    }
+
+    let (every, on) = match text.split_once(" on ") {
+        Some((every, on)) => (every.trim(), Some(on.trim())),
+        None => (text.as_str(), None),
+    };
+
+    let mut interval = 1;
+    let mut by_day: Option<Vec<&str>> = None;
+
+    let frequency = match every {
+        "daily" => "DAILY",
+        "weekly" => "WEEKLY",
+        "monthly" => "MONTHLY",
+        "yearly" | "annually" => "YEARLY",
+        "every weekday" => {
+            by_day = Some(vec!["MO", "TU", "WE", "TH", "FR"]);
+            "WEEKLY"
+        }
+        "every weekend" => {
+            by_day = Some(vec!["SA", "SU"]);
+            "WEEKLY"
+        }
+        _ => {
+            let rest = every.strip_prefix("every ").ok_or_else(|| invalid(input))?;
+            let (count, unit) = match rest.split_once(' ') {
+                Some(("other", unit)) => (Some(2), unit),
+                Some((count, unit)) => match count.parse::<u32>() {
+                    Ok(n) if n > 0 => (Some(n), unit),
+                    _ => (None, rest),
+                },
+                None => (None, rest),
+            };
+
+            match parse_frequency(unit) {
+                Some(frequency) => {
+                    interval = count.unwrap_or(1);
+                    frequency
+                }
+                None if count.is_none() => {
+                    by_day = Some(parse_weekdays(rest).ok_or_else(|| invalid(input))?);
+                    "WEEKLY"
+                }
+                None => return Err(invalid(input)),
+            }
+        }
+    };
+
+    let mut rule = format!("RRULE:FREQ={};INTERVAL={}", frequency, interval);
+
+    match (frequency, on) {
+        (_, None) => {}
+        ("WEEKLY", Some(days)) if by_day.is_none() => {
+            by_day = Some(parse_weekdays(days).ok_or_else(|| invalid(input))?);
+        }
+        ("MONTHLY", Some(position)) => {
+            let position = parse_month_position(position).ok_or_else(|| invalid(input))?;
+            rule.push(';');
+            rule.push_str(&position);
+        }
+        _ => return Err(invalid(input)),
+    }
+
+    if let Some(days) = by_day {
+        rule.push_str(&format!(";BYDAY={}", days.join(",")));
+    }
+
+    Ok(Some(rule))
+}
+
+fn weekday_name(code: &str) -> String {
+    WEEKDAYS
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| {
+            let mut name = name.to_string();
+            name[..1].make_ascii_uppercase();
+            name
+        })
+        .unwrap_or(code.to_string())
+}
+
+fn describe_month_day(day: &str) -> Option<String> {
+    match day.parse::<i32>().ok()? {
+        -1 => Some(String::from("on the last day")),
+        day if day > 0 => Some(format!("on day {}", day)),
+        _ => None,
+    }
+}
+
+fn describe_month_weekday(by_day: &str) -> Option<String> {
+    let split = by_day.find(|c: char| c.is_ascii_alphabetic())?;
+    let (position, code) = by_day.split_at(split);
+    let position: i32 = position.parse().ok()?;
+    let ordinal = ORDINALS.iter().find(|(_, p)| *p == position)?.0;
+    Some(format!("on the {} {}", ordinal, weekday_name(code)))
+}
+
+/// Human readable form of a `repeatFlag`, e.g. "Every 2 weeks on Monday, Thursday".
+/// Rules that can't be described are returned as they are.
+pub fn describe_repeat(rule: &str) -> String {
+    let body = rule.strip_prefix("RRULE:").unwrap_or(rule);
+    let mut frequency = None;
+    let mut interval: u32 = 1;
+    let mut by_day = None;
+    let mut by_month_day = None;
+
+    for part in body.split(';') {
+        match part.split_once('=') {
+            Some(("FREQ", value)) => frequency = Some(value),
+            Some(("INTERVAL", value)) => interval = value.parse().unwrap_or(1),
+            Some(("BYDAY", value)) => by_day = Some(value),
+            Some(("BYMONTHDAY", value)) => by_month_day = Some(value),
+            _ => {}
+        }
+    }
+
+    let unit = match frequency {
+        Some("DAILY") => "day",
+        Some("WEEKLY") => "week",
+        Some("MONTHLY") => "month",
+        Some("YEARLY") => "year",
+        _ => return rule.to_string(),
+    };
+
+    if unit == "week" && interval == 1 {
+        match by_day {
+            Some("MO,TU,WE,TH,FR") => return String::from("Every weekday"),
+            Some("SA,SU") => return String::from("Every weekend"),
+            _ => {}
+        }
+    }
+
+    let mut description = if interval == 1 {
+        format!("Every {}", unit)
+    } else {
+        format!("Every {} {}s", interval, unit)
+    };
+
+    let on = match (unit, by_day, by_month_day) {
+        ("week", Some(days), _) => Some(format!(
+            "on {}",
+            days.split(',')
+                .map(weekday_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        ("month", Some(day), _) => describe_month_weekday(day),
+        ("month", None, Some(day)) => describe_month_day(day),
+        _ => None,
+    };
+
+    if let Some(on) = on {
+        description.push(' ');
+        description.push_str(&on);
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> String {
+        parse_repeat(input).unwrap().unwrap()
+    }
+
+    #[test]
+    fn parses_simple_frequencies() {
+        assert_eq!(parse("daily"), "RRULE:FREQ=DAILY;INTERVAL=1");
+        assert_eq!(parse("Weekly"), "RRULE:FREQ=WEEKLY;INTERVAL=1");
+        assert_eq!(parse("every 3 days"), "RRULE:FREQ=DAILY;INTERVAL=3");
+        assert_eq!(parse("every other week"), "RRULE:FREQ=WEEKLY;INTERVAL=2");
+        assert_eq!(parse("every year"), "RRULE:FREQ=YEARLY;INTERVAL=1");
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(
+            parse("every weekday"),
+            "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR"
+        );
+        assert_eq!(
+            parse("every weekend"),
+            "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=SA,SU"
+        );
+        assert_eq!(
+            parse("every mon and thu"),
+            "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TH"
+        );
+        assert_eq!(
+            parse("every 2 weeks on mon,thu"),
+            "RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH"
+        );
+    }
+
+    #[test]
+    fn parses_monthly_positions() {
+        assert_eq!(
+            parse("monthly on the 15th"),
+            "RRULE:FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=15"
+        );
+        assert_eq!(
+            parse("monthly on the last day"),
+            "RRULE:FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=-1"
+        );
+        assert_eq!(
+            parse("monthly on the last friday"),
+            "RRULE:FREQ=MONTHLY;INTERVAL=1;BYDAY=-1FR"
+        );
+        assert_eq!(
+            parse("every 2 months on the second tuesday"),
+            "RRULE:FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU"
+        );
+    }
+
+    #[test]
+    fn accepts_raw_rules() {
+        assert_eq!(
+            parse("RRULE:FREQ=WEEKLY;BYDAY=SU"),
+            "RRULE:FREQ=WEEKLY;BYDAY=SU"
+        );
+        assert_eq!(parse("freq=daily;count=5"), "RRULE:FREQ=DAILY;COUNT=5");
+        assert!(parse_repeat("RRULE:BYDAY=SU").is_err());
+        assert!(parse_repeat("RRULE:FREQ=HOURLY").is_err());
+    }
+
+    #[test]
+    fn removes_the_recurrence_with_none() {
+        for input in ["none", "Never", "off"] {
+            assert_eq!(parse_repeat(input).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_repeats() {
+        for input in [
+            "sometimes",
+            "every 0 days",
+            "every 2 fortnights",
+            "monthly on the 32nd",
+            "monthly on the ninth friday",
+            "daily on monday",
+            "every weekday on mon",
+        ] {
+            assert!(
+                matches!(parse_repeat(input), Err(Error::Validation(_))),
+                "'{}' should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn describes_parsed_repeats() {
+        let cases = [
+            ("daily", "Every day"),
+            ("every 3 days", "Every 3 days"),
+            ("every weekday", "Every weekday"),
+            ("every weekend", "Every weekend"),
+            (
+                "every 2 weeks on mon,thu",
+                "Every 2 weeks on Monday, Thursday",
+            ),
+            ("monthly on the 15th", "Every month on day 15"),
+            ("monthly on the last day", "Every month on the last day"),
+            (
+                "monthly on the last friday",
+                "Every month on the last Friday",
+            ),
+            ("RRULE:FREQ=WEEKLY;BYDAY=SU", "Every week on Sunday"),
+            ("yearly", "Every year"),
+        ];
+        for (input, description) in cases {
+            assert_eq!(describe_repeat(&parse(input)), description, "{}", input);
+        }
+    }
+
+    #[test]
+    fn returns_unknown_rules_as_they_are() {
+        assert_eq!(describe_repeat("RRULE:FREQ=HOURLY"), "RRULE:FREQ=HOURLY");
+        assert_eq!(describe_repeat("garbage"), "garbage");
+    }
+}
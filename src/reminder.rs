@@ -0,0 +1,206 @@
+//! Translate reminder expressions ("15m before", "at 9:00am") to TickTick's
+//! `TRIGGER:` values, which are ISO-8601 durations relative to the due date.
+
+use chrono::Timelike;
+
+use crate::{
+    dates::{parse_duration, parse_time},
+    error::{Error, Result},
+};
+
+const ACCEPTED_FORMATS: &str = "Accepted formats:
+  on time
+  15m before | 1h before | 1h30m before | 1d before | 1w before
+  at 9:00am | at 9am | at 14:30 (on the due day, all-day or date-only tasks)
+  none (remove every reminder)";
+
+fn invalid(input: &str) -> Error {
+    Error::Validation(format!(
+        "Cannot understand reminder '{}'.\n{}",
+        input, ACCEPTED_FORMATS
+    ))
+}
+
+fn format_trigger(minutes: i64) -> String {
+    if minutes == 0 {
+        return String::from("TRIGGER:PT0S");
+    }
+
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.abs();
+    format!(
+        "TRIGGER:{}P{}DT{}H{}M0S",
+        sign,
+        minutes / (60 * 24),
+        minutes / 60 % 24,
+        minutes % 60
+    )
+}
+
+/// Parse a reminder expression into a `TRIGGER:` value.
+/// Triggers count from the due time, so "at 9am" only works when the task is due at midnight,
+/// which `date_only` tells (all-day tasks or due dates without a time).
+pub fn parse_reminder(input: &str, date_only: bool) -> Result<String> {
+    let text = input.trim().to_lowercase();
+
+    if text == "on time" || text == "at due" {
+        return Ok(format_trigger(0));
+    }
+
+    if let Some(before) = text.strip_suffix(" before") {
+        let minutes = parse_duration(before).ok_or_else(|| invalid(input))?;
+        return Ok(format_trigger(-minutes));
+    }
+
+    if let Some(at) = text.strip_prefix("at ") {
+        let time = parse_time(at).ok_or_else(|| invalid(input))?;
+        if !date_only {
+            return Err(Error::Validation(format!(
+                "'{}' needs an all-day task or a due date without a time, use e.g. \"1h before\"",
+                input.trim()
+            )));
+        }
+        return Ok(format_trigger((time.hour() * 60 + time.minute()) as i64));
+    }
+
+    Err(invalid(input))
+}
+
+/// Parse every `--remind` value, a single "none" clears the reminders
+pub fn parse_reminders(inputs: &[String], date_only: bool) -> Result<Vec<String>> {
+    if let [only] = inputs {
+        if only.trim().eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+    }
+    inputs
+        .iter()
+        .map(|input| parse_reminder(input, date_only))
+        .collect()
+}
+
+/// Read a `TRIGGER:` duration back into signed minutes
+fn trigger_minutes(trigger: &str) -> Option<i64> {
+    let duration = trigger.strip_prefix("TRIGGER:")?;
+    let (sign, duration) = match duration.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, duration),
+    };
+    let duration = duration.strip_prefix('P')?;
+
+    let mut total = 0;
+    let mut number = String::new();
+    for c in duration.chars() {
+        match c {
+            'T' => continue,
+            c if c.is_ascii_digit() => number.push(c),
+            unit => {
+                let value: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => value * 60 * 24 * 7,
+                    'D' => value * 60 * 24,
+                    'H' => value * 60,
+                    'M' => value,
+                    'S' => 0,
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(sign * total)
+}
+
+fn format_duration(minutes: i64) -> String {
+    let parts = [
+        (minutes / (60 * 24), "d"),
+        (minutes / 60 % 24, "h"),
+        (minutes % 60, "m"),
+    ];
+    parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect()
+}
+
+/// Human readable form of a reminder trigger, e.g. "15m before" or "at 09:00"
+pub fn describe_reminder(trigger: &str) -> String {
+    match trigger_minutes(trigger) {
+        Some(0) => String::from("on time"),
+        Some(minutes) if minutes < 0 => format!("{} before", format_duration(-minutes)),
+        Some(minutes) if minutes < 60 * 24 => {
+            format!("at {:02}:{:02}", minutes / 60, minutes % 60)
+        }
+        _ => trigger.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Result<String> {
+        parse_reminder(input, true)
+    }
+
+    #[test]
+    fn parses_reminders_before_the_due_time() {
+        assert_eq!(parse("on time").unwrap(), "TRIGGER:PT0S");
+        assert_eq!(parse("15m before").unwrap(), "TRIGGER:-P0DT0H15M0S");
+        assert_eq!(parse("1h30m before").unwrap(), "TRIGGER:-P0DT1H30M0S");
+        assert_eq!(parse("1d before").unwrap(), "TRIGGER:-P1DT0H0M0S");
+        assert_eq!(parse("1w before").unwrap(), "TRIGGER:-P7DT0H0M0S");
+    }
+
+    #[test]
+    fn parses_reminders_at_a_time_of_the_due_day() {
+        assert_eq!(parse("at 9:00am").unwrap(), "TRIGGER:P0DT9H0M0S");
+        assert_eq!(parse("At 2:30pm").unwrap(), "TRIGGER:P0DT14H30M0S");
+        assert_eq!(parse("at 14:30").unwrap(), "TRIGGER:P0DT14H30M0S");
+    }
+
+    #[test]
+    fn rejects_times_of_day_for_tasks_due_at_a_time() {
+        let error = parse_reminder("at 9am", false).unwrap_err();
+        assert!(matches!(error, Error::Validation(message) if message.contains("all-day")));
+        assert!(parse_reminder("15m before", false).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_reminders() {
+        for input in ["soon", "15 before", "at lunch", "15m after"] {
+            assert!(
+                matches!(parse(input), Err(Error::Validation(_))),
+                "'{}' should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn clears_reminders_with_none() {
+        let none = vec![String::from("None")];
+        assert!(parse_reminders(&none, false).unwrap().is_empty());
+
+        let several = vec![String::from("on time"), String::from("1h before")];
+        assert_eq!(parse_reminders(&several, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn describes_reminders() {
+        assert_eq!(describe_reminder("TRIGGER:PT0S"), "on time");
+        assert_eq!(describe_reminder("TRIGGER:-PT15M"), "15m before");
+        assert_eq!(describe_reminder("TRIGGER:-P1DT2H0M0S"), "1d2h before");
+        assert_eq!(describe_reminder("TRIGGER:-P1W"), "7d before");
+        assert_eq!(describe_reminder("TRIGGER:P0DT9H0M0S"), "at 09:00");
+        assert_eq!(describe_reminder("garbage"), "garbage");
+    }
+
+    #[test]
+    fn describes_parsed_reminders_as_written() {
+        for input in ["on time", "15m before", "1h30m before", "at 09:00"] {
+            assert_eq!(describe_reminder(&parse(input).unwrap()), input);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    error::{Error, Result},
+    services::tasks::{find_task, save_task},
+    tick_tick_api::{ChecklistItem, Task},
+    ItemArgs,
+};
+
+fn no_items() -> Error {
+    Error::NotFound(String::from("Task has no checklist items"))
+}
+
+/// Find the position of a checklist item by id, 1-based index or title
+fn find_item(items: &[ChecklistItem], query: &str) -> Result<usize> {
+    if let Some(pos) = items.iter().position(|i| i.id.as_deref() == Some(query)) {
+        return Ok(pos);
+    }
+
+    if let Ok(index) = query.parse::<usize>() {
+        if index >= 1 && index <= items.len() {
+            return Ok(index - 1);
+        }
+    }
+
+    let needle = query.to_lowercase();
+    let matches: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.title.to_lowercase().contains(&needle))
+        .map(|(pos, _)| pos)
+        .collect();
+
+    if let Some(&exact) = matches
+        .iter()
+        .find(|&&pos| items[pos].title.to_lowercase() == needle)
+    {
+        return Ok(exact);
+    }
+
+    match matches.len() {
+        0 => Err(Error::NotFound(format!(
+            "No checklist item found matching '{}'",
+            query
+        ))),
+        1 => Ok(matches[0]),
+        _ => Err(Error::Validation(format!(
+            "'{}' matches several checklist items, use the item number instead",
+            query
+        ))),
+    }
+}
+
+pub async fn add_item(args: &ItemArgs) -> Result<Task> {
+    let mut task = find_task(args.project.clone(), &args.task).await?;
+    let items = task.items.get_or_insert_with(Vec::new);
+
+    let sort_order = items
+        .iter()
+        .map(|i| i.sort_order)
+        .max()
+        .map_or(0, |o| o + 1);
+    items.push(ChecklistItem {
+        id: None,
+        title: args.item.clone(),
+        status: 0,
+        sort_order,
+        start_date: None,
+    });
+
+    save_task(&task).await
+}
+
+pub async fn set_item_status(args: &ItemArgs, checked: bool) -> Result<Task> {
+    let mut task = find_task(args.project.clone(), &args.task).await?;
+    let items = task.items.as_mut().ok_or_else(no_items)?;
+
+    let pos = find_item(items, &args.item)?;
+    items[pos].status = if checked { 1 } else { 0 };
+
+    save_task(&task).await
+}
+
+pub async fn remove_item(args: &ItemArgs) -> Result<Task> {
+    let mut task = find_task(args.project.clone(), &args.task).await?;
+    let items = task.items.as_mut().ok_or_else(no_items)?;
+
+    let pos = find_item(items, &args.item)?;
+    items.remove(pos);
+
+    save_task(&task).await
+}
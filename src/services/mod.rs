@@ -1,2 +1,3 @@
+pub mod items;
 pub mod projects;
 pub mod tasks;
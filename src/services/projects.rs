@@ -1,90 +1,400 @@
+use std::io::{self, IsTerminal};
+
+use dialoguer::{Confirm, Select};
+
 use crate::{
     client, config,
-    tick_tick_api::{Project, ProjectTaskResponse, Task},
+    error::{Error, Result},
+    tick_tick_api::{Column, Project, ProjectBody, ProjectTaskResponse, Task},
+    ui::views::{print_columns, print_projects},
+    ProjectArchiveArgs, ProjectColumnsArgs, ProjectCreateArgs, ProjectDeleteArgs, ProjectListArgs,
+    ProjectRenameArgs,
 };
 
-pub async fn get_project_tasks(
-    project_id: &String,
-) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
-    println!("{:?}", project_id);
-    let response = client::client()
-        .get(format!(
-            "{}/open/v1/project/{}/data",
-            &config::get().api_host,
-            project_id
-        ))
-        .send()
-        .await?;
-
-    let body = response.text().await?;
+pub async fn get_project_data(project_id: &str) -> Result<ProjectTaskResponse> {
+    client::client().get_project_data(project_id).await
+}
 
-    let project_data: ProjectTaskResponse = serde_json::from_str(&body)?;
+pub async fn get_project_tasks(project_id: &str) -> Result<Vec<Task>> {
+    let project_data = get_project_data(project_id).await?;
 
     if let Some(tasks) = project_data.tasks {
         return Ok(tasks);
     }
-    Err("No tasks found on project".into())
+    Err(Error::NotFound(String::from("No tasks found on project")))
 }
 
-pub async fn get_project_task(
-    project_id: Option<String>,
-    id: &str,
-) -> Result<Task, Box<dyn std::error::Error>> {
+pub async fn get_project_columns(project_id: &str) -> Result<Vec<Column>> {
+    let mut columns = get_project_data(project_id).await?.columns;
+    columns.sort_by_key(|c| c.sort_order);
+    Ok(columns)
+}
+
+/// Find a kanban column of a project by id, exact name or unique name prefix
+pub async fn find_column(project_id: &str, query: &str) -> Result<Column> {
+    let columns = get_project_columns(project_id).await?;
+    if columns.is_empty() {
+        return Err(Error::NotFound(String::from(
+            "The project has no kanban columns",
+        )));
+    }
+
+    let needle = query.to_lowercase();
+    if let Some(column) = columns
+        .iter()
+        .find(|c| c.id == query || c.name.to_lowercase() == needle)
+    {
+        return Ok(column.clone());
+    }
+
+    let matches: Vec<&Column> = columns
+        .iter()
+        .filter(|c| c.name.to_lowercase().starts_with(&needle))
+        .collect();
+    if let [column] = matches.as_slice() {
+        return Ok((*column).clone());
+    }
+
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    Err(Error::NotFound(format!(
+        "No single column matches '{}', columns are: {}",
+        query,
+        names.join(", ")
+    )))
+}
+
+pub async fn get_project_task(project_id: Option<String>, id: &str) -> Result<Task> {
     let name = project_id.unwrap_or("inbox".to_string());
-    let response = client::client()
-        .get(format!(
-            "{}/open/v1/project/{}/tastk/{}",
-            &config::get().api_host,
-            name,
-            id
-        ))
-        .send()
-        .await?;
+    client::client().get_task(&name, id).await
+}
 
-    let task_response = response.json::<Task>().await?;
-    Ok(task_response)
+pub async fn get_projects() -> Result<Vec<Project>> {
+    client::client().get_projects().await
 }
 
-pub async fn get_projects() -> Result<Vec<Project>, Box<dyn std::error::Error>> {
-    let response = client::client()
-        .get(format!("{}/open/v1/project", &config::get().api_host))
-        .send()
-        .await?;
+/// Resolve a project id to its display name, falls back to the id when the project is unknown
+pub async fn get_project_name(project_id: &str) -> Result<String> {
+    if project_id.starts_with("inbox") {
+        return Ok(String::from("Inbox"));
+    }
 
-    let projects: Vec<Project> = response.json().await?;
-    Ok(projects)
+    match client::client().get_project(project_id).await {
+        Ok(project) => Ok(project.name),
+        Err(Error::NotFound(_)) => Ok(project_id.to_string()),
+        Err(e) => Err(e),
+    }
 }
 
+/// Projects the API knows by a fixed id but doesn't list, e.g. "Inbox"
 fn is_white_list_value(name: &str) -> bool {
-    let white_list: Vec<String> = vec![String::from("inbox")];
+    let white_list = ["inbox"];
+
+    white_list.iter().any(|v| v.eq_ignore_ascii_case(name))
+}
+
+enum ProjectMatch {
+    Found(Project),
+    Ambiguous(Vec<Project>),
+    NotFound,
+}
+
+/// Every query character appears in the name, in order ("wrk" matches "Work")
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut chars = name.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+/// Match a project by exact id, then exact name, then unique prefix, then fuzzy name.
+/// The first rule with any match wins, several matches for that rule are ambiguous.
+fn match_project(projects: Vec<Project>, query: &str) -> ProjectMatch {
+    let needle = query.to_lowercase();
+
+    let rules: [&dyn Fn(&Project) -> bool; 4] = [
+        &|p| p.id == query,
+        &|p| p.name.to_lowercase() == needle,
+        &|p| p.name.to_lowercase().starts_with(&needle),
+        &|p| {
+            let name = p.name.to_lowercase();
+            name.contains(&needle) || is_subsequence(&needle, &name)
+        },
+    ];
 
-    let value = white_list.iter().find(|&v| v == name);
+    for rule in rules {
+        let mut matches: Vec<Project> = projects.iter().filter(|p| rule(p)).cloned().collect();
+        match matches.len() {
+            0 => continue,
+            1 => return ProjectMatch::Found(matches.remove(0)),
+            _ => return ProjectMatch::Ambiguous(matches),
+        }
+    }
+    ProjectMatch::NotFound
+}
+
+/// Ask which project was meant, or list the candidates when there is no terminal
+fn choose_project(query: &str, mut candidates: Vec<Project>) -> Result<Project> {
+    let labels: Vec<String> = candidates
+        .iter()
+        .map(|p| format!("{} ({})", p.name, p.id))
+        .collect();
 
-    value.is_some()
+    if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        let selected = Select::new()
+            .with_prompt(format!("'{}' matches several projects, pick one", query))
+            .items(&labels)
+            .default(0)
+            .interact()?;
+        return Ok(candidates.swap_remove(selected));
+    }
+
+    Err(Error::Validation(format!(
+        "'{}' matches several projects, use a longer name or the project id:\n  {}",
+        query,
+        labels.join("\n  ")
+    )))
 }
 
-async fn get_project_id(project_name: &str) -> Result<String, Box<dyn std::error::Error>> {
+async fn find_project(project_name: &str) -> Result<Project> {
     let projects = get_projects().await?;
-    if let Some(current_project) = projects.iter().find(|p| {
-        let lower_case_name = &p.name.to_lowercase();
-        return lower_case_name.contains(&project_name.to_lowercase());
-    }) {
-        return Ok(current_project.id.clone());
+
+    match match_project(projects, project_name) {
+        ProjectMatch::Found(project) => Ok(project),
+        ProjectMatch::Ambiguous(candidates) => choose_project(project_name, candidates),
+        ProjectMatch::NotFound => Err(Error::NotFound(format!(
+            "No project found matching '{}', see: tick project list",
+            project_name
+        ))),
+    }
+}
+
+async fn get_project_id(project_name: &str) -> Result<String> {
+    let project = find_project(project_name).await?;
+    Ok(project.id)
+}
+
+pub async fn get_project(name: Option<String>) -> Result<String> {
+    let project = match name {
+        Some(user_project) => user_project,
+        None => config::AppConfig::load()?.default_project,
+    };
+
+    // The inbox is not part of the project list, the API accepts "inbox" as its id
+    if is_white_list_value(&project) {
+        return Ok(String::from("inbox"));
+    }
+    get_project_id(&project).await
+}
+
+/// Resolve a project that can be modified, inbox is managed by TickTick itself
+async fn find_editable_project(name: &str) -> Result<Project> {
+    if is_white_list_value(name) {
+        return Err(Error::Validation(String::from("Inbox cannot be modified")));
+    }
+    find_project(name).await
+}
+
+fn validate_color(color: &str) -> Result<()> {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::Validation(format!(
+            "Invalid color '{}', use a hex value like #F18181",
+            color
+        )));
+    }
+    Ok(())
+}
+
+async fn save_project(project_id: Option<&str>, body: &ProjectBody) -> Result<Project> {
+    match project_id {
+        Some(id) => client::client().update_project(id, body).await,
+        None => client::client().create_project(body).await,
+    }
+}
+
+pub async fn list_projects(args: &ProjectListArgs) -> Result<()> {
+    let projects: Vec<Project> = get_projects()
+        .await?
+        .into_iter()
+        .filter(|p| args.all || !p.is_archived())
+        .collect();
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&projects)?);
+        return Ok(());
+    }
+
+    print_projects(&projects);
+    Ok(())
+}
+
+pub async fn list_columns(args: &ProjectColumnsArgs) -> Result<()> {
+    let project_id = get_project(Some(args.project.clone())).await?;
+    let columns = get_project_columns(&project_id).await?;
+
+    if columns.is_empty() {
+        println!("The project has no kanban columns");
+        return Ok(());
+    }
+
+    print_columns(&columns);
+    Ok(())
+}
+
+pub async fn create_project(args: &ProjectCreateArgs) -> Result<Project> {
+    if let Some(color) = &args.color {
+        validate_color(color)?;
+    }
+
+    let body = ProjectBody {
+        name: Some(args.name.clone()),
+        color: args.color.clone(),
+        view_mode: args.view,
+        kind: args.kind,
+        ..Default::default()
+    };
+    save_project(None, &body).await
+}
+
+pub async fn rename_project(args: &ProjectRenameArgs) -> Result<Project> {
+    let project = find_editable_project(&args.project).await?;
+
+    let body = ProjectBody {
+        name: Some(args.name.clone()),
+        ..Default::default()
+    };
+    save_project(Some(&project.id), &body).await
+}
+
+pub async fn archive_project(args: &ProjectArchiveArgs) -> Result<Project> {
+    let project = find_editable_project(&args.project).await?;
+
+    let body = ProjectBody {
+        closed: Some(!args.restore),
+        ..Default::default()
+    };
+    save_project(Some(&project.id), &body).await
+}
+
+/// Delete a project and all of its tasks, asks for confirmation unless `--yes`
+pub async fn delete_project(args: &ProjectDeleteArgs) -> Result<()> {
+    let project = find_editable_project(&args.project).await?;
+
+    if !args.yes {
+        if !io::stdin().is_terminal() {
+            return Err(Error::Validation(String::from(
+                "Cannot ask for confirmation without a terminal, pass --yes",
+            )));
+        }
+
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Delete project '{}' and all of its tasks?",
+                project.name
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            println!("Nothing deleted");
+            return Ok(());
+        }
     }
-    Err("Cannot get project id".into())
+
+    client::client().delete_project(&project.id).await?;
+
+    println!("🗑️  Deleted: {}", project.name);
+    Ok(())
 }
 
-pub async fn get_project(name: Option<String>) -> Result<String, Box<dyn std::error::Error>> {
-    let default_project = &config::AppConfig::load()?.default_project;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, name: &str) -> Project {
+        Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            kind: None,
+            sort_order: 0,
+            color: None,
+            closed: None,
+            group_id: None,
+            view_mode: None,
+            permission: None,
+        }
+    }
+
+    fn projects() -> Vec<Project> {
+        vec![
+            project("p1", "Homework"),
+            project("p2", "Work"),
+            project("p3", "Workout"),
+            project("p4", "Shopping"),
+            project("p5", "Side projects"),
+        ]
+    }
 
-    if let Some(user_project) = name {
-        if is_white_list_value(&user_project) {
-            return Ok(user_project);
+    fn found(query: &str) -> Option<String> {
+        match match_project(projects(), query) {
+            ProjectMatch::Found(project) => Some(project.id),
+            _ => None,
         }
-        let project_id = get_project_id(&user_project).await?;
-        return Ok(project_id);
-    } else {
-        let project_id = get_project_id(&default_project).await?;
-        return Ok(project_id);
+    }
+
+    fn ambiguous(query: &str) -> Vec<String> {
+        match match_project(projects(), query) {
+            ProjectMatch::Ambiguous(candidates) => candidates.into_iter().map(|p| p.id).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_the_id_first() {
+        assert_eq!(found("p3").as_deref(), Some("p3"));
+    }
+
+    #[test]
+    fn prefers_the_exact_name_over_prefixes_and_substrings() {
+        // "Work" is also a prefix of "Workout" and inside "Homework"
+        assert_eq!(found("Work").as_deref(), Some("p2"));
+        assert_eq!(found("work").as_deref(), Some("p2"));
+    }
+
+    #[test]
+    fn matches_a_unique_prefix() {
+        assert_eq!(found("Worko").as_deref(), Some("p3"));
+        assert_eq!(found("shop").as_deref(), Some("p4"));
+    }
+
+    #[test]
+    fn reports_several_prefix_matches_as_ambiguous() {
+        let projects = vec![project("p1", "Work"), project("p2", "Workout")];
+        match match_project(projects, "wor") {
+            ProjectMatch::Ambiguous(candidates) => assert_eq!(candidates.len(), 2),
+            _ => panic!("expected an ambiguous match"),
+        }
+    }
+
+    #[test]
+    fn falls_back_to_substrings_and_subsequences() {
+        assert_eq!(found("homew").as_deref(), Some("p1"));
+        assert_eq!(found("pping").as_deref(), Some("p4"));
+        assert_eq!(found("sdprj").as_deref(), Some("p5"));
+        assert_eq!(ambiguous("ork"), vec!["p1", "p2", "p3"]);
+    }
+
+    #[test]
+    fn reports_unknown_projects() {
+        assert!(matches!(
+            match_project(projects(), "garden"),
+            ProjectMatch::NotFound
+        ));
+    }
+
+    #[test]
+    fn recognises_the_inbox_in_any_case() {
+        assert!(is_white_list_value("inbox"));
+        assert!(is_white_list_value("Inbox"));
+        assert!(is_white_list_value("INBOX"));
+        assert!(!is_white_list_value("Inboxes"));
     }
 }
@@ -1,81 +1,872 @@
-use chrono::{NaiveDate, NaiveDateTime, TimeZone};
-use chrono_tz::Tz;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, IsTerminal},
+};
+
+use chrono::Duration;
+use dialoguer::Confirm;
 
 use crate::{
-    client, config, services,
-    tick_tick_api::{CreateTaskBody, TaskPriority},
-    ui::views::render_tasks,
-    CreateArgs,
+    client, config,
+    dates::{is_local_midnight, parse_api_date, parse_duration, parse_flexible_date, to_api_date},
+    editor,
+    error::{Error, Result},
+    quick_add::{parse_quick_add, QuickAdd},
+    recurrence, reminder, services,
+    tick_tick_api::{ChecklistItem, CreateTaskBody, MoveTaskBody, Task, TaskPriority},
+    ui::{
+        board::render_board,
+        views::{print_task_details, print_tasks, render_tasks},
+    },
+    AddArgs, ContentArgs, CreateArgs, DeleteArgs, GetArgs, MoveArgs, ReopenArgs, ScheduleArgs,
+    ShowArgs, StatusArgs, UpdateArgs,
 };
 
-fn parse_flexible_date(input: &str, timezone: &str) -> Option<String> {
-    // Parse the timezone
-    let tz: Tz = timezone.parse().ok()?;
+const TASK_STATUS_OPEN: u32 = 0;
 
-    // Format: YYYY-MM-DD HH:MMam/pm (e.g., "2025-12-16 2:00pm")
-    if let Ok(dt) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %I:%M%p") {
-        // Convert from local timezone to UTC
-        let local_dt = tz.from_local_datetime(&dt).single()?;
-        let utc_dt = local_dt.with_timezone(&chrono_tz::UTC);
-        return Some(utc_dt.format("%Y-%m-%dT%H:%M:%S+0000").to_string());
-    }
+/// Start, due and all-day values ready to be sent to the API
+#[derive(Default)]
+struct Schedule {
+    start_date: Option<String>,
+    due_date: Option<String>,
+    is_all_day: Option<bool>,
+}
+
+/// Combine --due, --start, --duration and --all-day into the dates of a task.
+/// A range or a duration fills in whichever end is missing, on update a duration
+/// alone counts from the dates the `current` task already has.
+fn parse_schedule(args: &ScheduleArgs, timezone: &str, current: Option<&Task>) -> Result<Schedule> {
+    let due = args
+        .due
+        .as_deref()
+        .map(|d| parse_flexible_date(d, timezone))
+        .transpose()?;
+    let start = args
+        .start
+        .as_deref()
+        .map(|d| parse_flexible_date(d, timezone))
+        .transpose()?;
+
+    let mut start_dt = match &start {
+        Some(span) => Some(span.start.unwrap_or(span.end)),
+        None => due.as_ref().and_then(|span| span.start),
+    };
+    let mut due_dt = match (&due, &start) {
+        (Some(span), _) => Some(span.end),
+        (None, Some(span)) if span.start.is_some() => Some(span.end),
+        _ => None,
+    };
+
+    if let Some(duration) = &args.duration {
+        let minutes = parse_duration(duration).ok_or_else(|| {
+            Error::Validation(format!(
+                "Cannot understand duration '{}', e.g. 45m, 1h30m, 2d",
+                duration
+            ))
+        })?;
+        let duration = Duration::minutes(minutes);
 
-    // Format: YYYY-MM-DD (e.g., "2025-12-25")
-    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
-        // Convert to datetime at midnight in local timezone, then to UTC
-        let naive_dt = date.and_hms_opt(0, 0, 0)?;
-        let local_dt = tz.from_local_datetime(&naive_dt).single()?;
-        let utc_dt = local_dt.with_timezone(&chrono_tz::UTC);
-        return Some(utc_dt.format("%Y-%m-%dT%H:%M:%S+0000").to_string());
+        if let (None, None, Some(task)) = (start_dt, due_dt, current) {
+            let existing = |date: &Option<String>| {
+                date.as_deref()
+                    .and_then(|date| parse_api_date(date, timezone))
+            };
+            start_dt = existing(&task.start_date);
+            if start_dt.is_none() {
+                due_dt = existing(&task.due_date);
+            }
+        }
+
+        match (start_dt, due_dt) {
+            (Some(start), None) => due_dt = Some(start + duration),
+            (None, Some(due)) => start_dt = Some(due - duration),
+            (Some(_), Some(_)) => {
+                return Err(Error::Validation(String::from(
+                    "--duration cannot be combined with both a start and a due date",
+                )))
+            }
+            (None, None) => {
+                return Err(Error::Validation(String::from(
+                    "--duration needs --start or --due",
+                )))
+            }
+        }
     }
 
-    None
+    Ok(Schedule {
+        start_date: start_dt.as_ref().map(to_api_date),
+        due_date: due_dt.as_ref().map(to_api_date),
+        is_all_day: args.all_day,
+    })
 }
 
 fn parse_priority(priority: &TaskPriority) -> u32 {
-    let value = match priority {
+    match priority {
         TaskPriority::Low => 1,
         TaskPriority::Mid => 3,
         TaskPriority::High => 5,
-    };
-    value
+    }
+}
+
+/// Read the description from the flag, a file or the editor.
+/// `None` means no description was requested, `current` pre-fills the editor.
+fn read_content(args: &ContentArgs, current: Option<&str>) -> Result<Option<String>> {
+    if let Some(content) = &args.content {
+        return Ok(Some(content.clone()));
+    }
+
+    if let Some(path) = &args.content_file {
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::Io(format!("Cannot read {}: {}", path.display(), e)))?;
+        return Ok(Some(content.trim_end().to_string()));
+    }
+
+    if args.edit {
+        let content = editor::edit(current.unwrap_or_default())?;
+        return Ok(Some(content));
+    }
+
+    Ok(None)
+}
+
+/// A recurrence repeats from the start or due date, without either it never fires
+fn missing_repeat_date() -> Error {
+    Error::Validation(String::from(
+        "Recurring tasks need a date to repeat from, pass --due or --start",
+    ))
+}
+
+/// Parse `--remind` for a task, reminders count from the due date so one is required
+fn parse_task_reminders(
+    remind: &[String],
+    due_date: Option<&str>,
+    is_all_day: bool,
+) -> Result<Vec<String>> {
+    let date_only =
+        due_date.is_none_or(|due| is_all_day || is_local_midnight(due, &config::get().time_zone));
+    let reminders = reminder::parse_reminders(remind, date_only)?;
+
+    if !reminders.is_empty() && due_date.is_none() {
+        return Err(Error::Validation(String::from(
+            "Reminders need a due date, pass --due",
+        )));
+    }
+    Ok(reminders)
 }
 
-pub async fn create_task(args: &CreateArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let project_id = services::projects::get_project(Some(args.project.clone())).await?;
+pub async fn create_task(args: &CreateArgs) -> Result<()> {
+    let project_id = services::projects::get_project(args.project.clone()).await?;
+
+    let schedule = parse_schedule(&args.schedule, &config::get().time_zone, None)?;
+
+    let column_id = match &args.column {
+        Some(column) => Some(
+            services::projects::find_column(&project_id, column)
+                .await?
+                .id,
+        ),
+        None => None,
+    };
 
     let mut body = CreateTaskBody {
         title: args.title.clone(),
         project_id: Some(project_id),
         tags: args.tags.clone(),
-        due_date: None,
+        start_date: schedule.start_date,
+        due_date: schedule.due_date,
+        is_all_day: schedule.is_all_day,
         time_zone: config::get().time_zone.clone(),
         priority: None,
+        items: None,
+        content: read_content(&args.content, None)?.filter(|c| !c.is_empty()),
+        repeat_flag: None,
+        reminders: None,
+        column_id,
     };
 
-    if let Some(due) = &args.due {
-        body.due_date = parse_flexible_date(due, &config::get().time_zone);
+    if let Some(repeat) = &args.repeat {
+        body.repeat_flag = recurrence::parse_repeat(repeat)?;
+        if body.repeat_flag.is_some() && body.start_date.is_none() && body.due_date.is_none() {
+            return Err(missing_repeat_date());
+        }
+    }
+
+    if !args.remind.is_empty() {
+        body.reminders = Some(parse_task_reminders(
+            &args.remind,
+            body.due_date.as_deref(),
+            body.is_all_day.unwrap_or(false),
+        )?);
     }
 
     if let Some(pr) = &args.priority {
         body.priority = Some(parse_priority(pr));
     }
 
-    let _ = client::client()
-        .post(format!("{}/open/v1/task", &config::get().api_host))
-        .json(&body)
-        .send()
-        .await?;
+    if !args.items.is_empty() {
+        let items = args
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, title)| ChecklistItem {
+                id: None,
+                title: title.clone(),
+                status: 0,
+                sort_order: i as i64,
+                start_date: None,
+            })
+            .collect();
+        body.items = Some(items);
+    }
+
+    client::client().create_task(&body).await?;
 
     Ok(())
 }
 
-pub async fn get_tasks(project: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+/// Create a task from quick-add text. Returns `false` on a dry run, when nothing was created.
+pub async fn add_task(args: &AddArgs) -> Result<bool> {
+    let text = args.text.join(" ");
+    let timezone = &config::get().time_zone;
+
+    let parsed = if args.no_parse {
+        QuickAdd {
+            title: text,
+            ..Default::default()
+        }
+    } else {
+        parse_quick_add(&text, timezone)
+    };
+
+    if parsed.title.is_empty() {
+        return Err(Error::Validation(String::from(
+            "The task needs a title besides the inline fields",
+        )));
+    }
+
+    if args.dry_run {
+        let due = match &parsed.due {
+            Some(due) => format!("{} ({})", parse_flexible_date(due, timezone)?.end, due),
+            None => String::from("-"),
+        };
+        let priority = parsed
+            .priority
+            .map_or(String::from("-"), |p| format!("{:?}", p));
+
+        println!("Title:    {}", parsed.title);
+        println!(
+            "Project:  {}",
+            parsed.project.as_deref().unwrap_or("(default)")
+        );
+        println!("Tags:     {}", parsed.tags.join(", "));
+        println!("Priority: {}", priority);
+        println!("Due:      {}", due);
+        return Ok(false);
+    }
+
+    let create_args = CreateArgs {
+        title: parsed.title,
+        project: parsed.project,
+        tags: parsed.tags,
+        priority: parsed.priority,
+        schedule: ScheduleArgs {
+            due: parsed.due,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    create_task(&create_args).await?;
+    Ok(true)
+}
+
+/// Fetch the tasks of inbox and every project concurrently.
+/// Returns the merged tasks (in project order) and a project id to name map.
+async fn get_all_tasks() -> Result<(Vec<Task>, HashMap<String, String>)> {
+    let projects = services::projects::get_projects().await?;
+
+    let mut project_names: HashMap<String, String> = HashMap::new();
+    let mut project_ids = vec![String::from("inbox")];
+    for project in projects {
+        project_ids.push(project.id.clone());
+        project_names.insert(project.id, project.name);
+    }
+
+    let handles: Vec<_> = project_ids
+        .into_iter()
+        .map(|project_id| {
+            tokio::spawn(async move {
+                services::projects::get_project_data(&project_id)
+                    .await
+                    .map(|data| data.tasks.unwrap_or_default())
+            })
+        })
+        .collect();
+
+    let mut tasks = Vec::new();
+    for handle in handles {
+        let project_tasks = handle.await??;
+        for task in project_tasks {
+            // Inbox tasks carry a per-user id like `inbox123456`
+            if task.project_id.starts_with("inbox") {
+                project_names
+                    .entry(task.project_id.clone())
+                    .or_insert(String::from("Inbox"));
+            }
+            tasks.push(task);
+        }
+    }
+
+    Ok((tasks, project_names))
+}
+
+pub async fn get_tasks(args: &GetArgs) -> Result<()> {
+    let (tasks, project_names) = if args.all {
+        let (tasks, project_names) = get_all_tasks().await?;
+        (tasks, Some(project_names))
+    } else {
+        let project_id = services::projects::get_project(args.project.clone()).await?;
+        let project_data = services::projects::get_project_data(&project_id).await?;
+        let tasks = project_data.tasks.unwrap_or_default();
+
+        // Kanban projects get the board unless the table was asked for
+        let interactive = !args.ids && io::stdout().is_terminal();
+        if !project_data.columns.is_empty() && !args.table && interactive {
+            render_board(project_data.columns, tasks).await?;
+            return Ok(());
+        }
+        (tasks, None)
+    };
+
+    if args.ids {
+        for task in &tasks {
+            println!("{}", task.id);
+        }
+        return Ok(());
+    }
+
+    if tasks.is_empty() {
+        println!("No tasks found");
+        return Ok(());
+    }
+
+    // Piped or redirected output gets plain rows instead of the interactive view
+    if !io::stdout().is_terminal() {
+        print_tasks(&tasks);
+        return Ok(());
+    }
+
+    render_tasks(tasks, project_names)?;
+
+    Ok(())
+}
+
+/// Find a task inside a project either by its id or by its title.
+/// Titles are matched case-insensitively, an exact title wins over partial matches.
+pub async fn find_task(project: Option<String>, query: &str) -> Result<Task> {
     let project_id = services::projects::get_project(project).await?;
-    println!("using project {:?}\n", project_id);
+
+    if query.chars().all(|c| c.is_ascii_alphanumeric()) {
+        if let Ok(task) =
+            services::projects::get_project_task(Some(project_id.clone()), query).await
+        {
+            return Ok(task);
+        }
+    }
+
     let tasks = services::projects::get_project_tasks(&project_id).await?;
+    let needle = query.to_lowercase();
+
+    let mut matches: Vec<Task> = tasks
+        .into_iter()
+        .filter(|t| t.title.to_lowercase().contains(&needle))
+        .collect();
+
+    if let Some(exact) = matches
+        .iter()
+        .position(|t| t.title.to_lowercase() == needle)
+    {
+        return Ok(matches.swap_remove(exact));
+    }
+
+    match matches.len() {
+        0 => Err(Error::NotFound(format!(
+            "No task found matching '{}'",
+            query
+        ))),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let candidates: Vec<String> = matches
+                .iter()
+                .map(|t| format!("  {}  {}", t.id, t.title))
+                .collect();
+            Err(Error::Validation(format!(
+                "'{}' matches several tasks, use the task id instead:\n{}",
+                query,
+                candidates.join("\n")
+            )))
+        }
+    }
+}
+
+pub async fn show_task(args: &ShowArgs) -> Result<()> {
+    let task = find_task(args.project.clone(), &args.task).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&task)?);
+        return Ok(());
+    }
+
+    let project_name = services::projects::get_project_name(&task.project_id).await?;
+    print_task_details(&task, &project_name);
+
+    Ok(())
+}
+
+pub async fn update_task(args: &UpdateArgs) -> Result<Task> {
+    let mut task = find_task(args.project.clone(), &args.task).await?;
+
+    if let Some(title) = &args.title {
+        task.title = title.clone();
+    }
+
+    if let Some(content) = read_content(&args.content, task.content.as_deref())? {
+        task.content = Some(content);
+    }
+
+    if let Some(tags) = &args.tags {
+        task.tags = Some(tags.iter().filter(|t| !t.is_empty()).cloned().collect());
+    }
+
+    let schedule = parse_schedule(&args.schedule, &config::get().time_zone, Some(&task))?;
+    if schedule.start_date.is_some() {
+        task.start_date = schedule.start_date;
+    }
+    if schedule.due_date.is_some() {
+        task.due_date = schedule.due_date;
+    }
+    if let Some(is_all_day) = schedule.is_all_day {
+        task.is_all_day = is_all_day;
+    }
+
+    if let Some(pr) = &args.priority {
+        task.priority = parse_priority(pr);
+    }
 
-    render_tasks(tasks)?;
+    if let Some(repeat) = &args.repeat {
+        // An empty flag clears the recurrence on the server
+        task.repeat_flag = Some(recurrence::parse_repeat(repeat)?.unwrap_or_default());
+        let repeats = task.repeat_flag.as_ref().is_some_and(|r| !r.is_empty());
+        if repeats && task.start_date.is_none() && task.due_date.is_none() {
+            return Err(missing_repeat_date());
+        }
+    }
+
+    if !args.remind.is_empty() {
+        task.reminders = Some(parse_task_reminders(
+            &args.remind,
+            task.due_date.as_deref(),
+            task.is_all_day,
+        )?);
+    }
+
+    if let Some(to) = &args.to {
+        let to_project_id = services::projects::get_project(Some(to.clone())).await?;
+        if to_project_id != task.project_id {
+            move_to_project(std::slice::from_ref(&task), &to_project_id).await?;
+            task.project_id = to_project_id;
+        }
+    }
+
+    if let Some(column) = &args.column {
+        task.column_id = Some(
+            services::projects::find_column(&task.project_id, column)
+                .await?
+                .id,
+        );
+    }
+
+    save_task(&task).await
+}
+
+/// Move tasks to another project in a single request
+async fn move_to_project(tasks: &[Task], to_project_id: &str) -> Result<()> {
+    let body: Vec<MoveTaskBody> = tasks
+        .iter()
+        .map(|task| MoveTaskBody {
+            from_project_id: task.project_id.clone(),
+            to_project_id: to_project_id.to_string(),
+            task_id: task.id.clone(),
+        })
+        .collect();
+
+    client::client().move_tasks(&body).await
+}
+
+/// Collect the tasks to move: the given ids or titles plus every task with `--tag`
+async fn find_tasks_to_move(args: &MoveArgs) -> Result<Vec<Task>> {
+    let mut tasks: Vec<Task> = Vec::new();
+
+    if let Some(tag) = &args.tag {
+        let project_id = services::projects::get_project(args.project.clone()).await?;
+        let tagged = services::projects::get_project_tasks(&project_id)
+            .await?
+            .into_iter()
+            .filter(|t| t.tags.iter().flatten().any(|t| t.eq_ignore_ascii_case(tag)));
+        tasks.extend(tagged);
+    }
+
+    for query in &args.tasks {
+        let task = find_task(args.project.clone(), query).await?;
+        if !tasks.iter().any(|t| t.id == task.id) {
+            tasks.push(task);
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Move tasks to another project and/or kanban column
+pub async fn move_tasks(args: &MoveArgs) -> Result<()> {
+    let mut tasks = find_tasks_to_move(args).await?;
+    if tasks.is_empty() {
+        return Err(Error::Validation(String::from("No tasks to move")));
+    }
+
+    if let Some(to) = &args.to {
+        let to_project_id = services::projects::get_project(Some(to.clone())).await?;
+        let project_name = services::projects::get_project_name(&to_project_id).await?;
+
+        let (moving, already_there): (Vec<Task>, Vec<Task>) = tasks
+            .into_iter()
+            .partition(|t| t.project_id != to_project_id);
+
+        if !moving.is_empty() {
+            move_to_project(&moving, &to_project_id).await?;
+        }
+        for task in &moving {
+            println!("✅ Moved: {} → {}", task.title, project_name);
+        }
+        for task in &already_there {
+            println!("➖ Already in {}: {}", project_name, task.title);
+        }
 
+        tasks = moving.into_iter().chain(already_there).collect();
+        for task in tasks.iter_mut() {
+            task.project_id = to_project_id.clone();
+        }
+    }
+
+    if let Some(column) = &args.column {
+        let mut failed = 0;
+        for task in tasks.iter_mut() {
+            let result = match services::projects::find_column(&task.project_id, column).await {
+                Ok(column) => {
+                    task.column_id = Some(column.id.clone());
+                    save_task(task).await.map(|_| column)
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(column) => println!("✅ Moved: {} → {}", task.title, column.name),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("❌ {}: {}", task.title, e);
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(Error::PartialFailure {
+                failed,
+                total: tasks.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Send the full task back to the API, the task id and etag come from a previous read
+pub async fn save_task(task: &Task) -> Result<Task> {
+    client::client().update_task(task).await
+}
+
+async fn complete_task(task: &Task) -> Result<()> {
+    client::client()
+        .complete_task(&task.project_id, &task.id)
+        .await
+}
+
+async fn reopen_task(task: &Task) -> Result<()> {
+    let mut task = task.clone();
+    task.status = TASK_STATUS_OPEN;
+    save_task(&task).await?;
     Ok(())
 }
+
+/// Project data only lists open tasks, so a completed task can only be fetched by its id
+async fn find_completed_task(project: Option<String>, id: &str) -> Result<Task> {
+    let project_id = services::projects::get_project(project).await?;
+    services::projects::get_project_task(Some(project_id), id)
+        .await
+        .map_err(|e| match e {
+            Error::NotFound(_) => Error::NotFound(format!(
+                "No task with id '{}', completed tasks can only be reopened by id",
+                id
+            )),
+            e => e,
+        })
+}
+
+pub async fn complete_tasks(args: &StatusArgs) -> Result<()> {
+    set_tasks_status(&args.tasks, &args.project, true).await
+}
+
+pub async fn reopen_tasks(args: &ReopenArgs) -> Result<()> {
+    set_tasks_status(&args.tasks, &args.project, false).await
+}
+
+/// Complete or reopen every task, a failing task doesn't stop the others
+async fn set_tasks_status(
+    tasks: &[String],
+    project: &Option<String>,
+    complete: bool,
+) -> Result<()> {
+    let mut failed = 0;
+
+    for query in tasks {
+        let task = if complete {
+            find_task(project.clone(), query).await
+        } else {
+            find_completed_task(project.clone(), query).await
+        };
+        let task = match task {
+            Ok(task) => task,
+            Err(e) => {
+                failed += 1;
+                eprintln!("❌ {}: {}", query, e);
+                continue;
+            }
+        };
+
+        let is_completed = task.status != TASK_STATUS_OPEN;
+        if is_completed == complete {
+            let state = if complete { "completed" } else { "open" };
+            println!("➖ Already {}: {}", state, task.title);
+            continue;
+        }
+
+        let result = if complete {
+            complete_task(&task).await
+        } else {
+            reopen_task(&task).await
+        };
+
+        match result {
+            Ok(_) if complete => println!("✅ Completed: {}", task.title),
+            Ok(_) => println!("🔄 Reopened: {}", task.title),
+            Err(e) => {
+                failed += 1;
+                eprintln!("❌ {}: {}", query, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::PartialFailure {
+            failed,
+            total: tasks.len(),
+        });
+    }
+    Ok(())
+}
+
+async fn delete_task(task: &Task) -> Result<()> {
+    client::client()
+        .delete_task(&task.project_id, &task.id)
+        .await
+}
+
+/// Collect the task queries from the arguments, `-` reads one id or title per line from stdin
+fn read_task_queries(tasks: &[String]) -> Result<Vec<String>> {
+    let mut queries = Vec::new();
+
+    for task in tasks {
+        if task == "-" {
+            for line in io::stdin().lock().lines() {
+                let line = line?;
+                let line = line.trim();
+                if !line.is_empty() {
+                    queries.push(line.to_string());
+                }
+            }
+        } else {
+            queries.push(task.clone());
+        }
+    }
+
+    Ok(queries)
+}
+
+pub async fn delete_tasks(args: &DeleteArgs) -> Result<()> {
+    let queries = read_task_queries(&args.tasks)?;
+    if queries.is_empty() {
+        return Err(Error::Validation(String::from("No tasks to delete")));
+    }
+
+    let mut tasks: Vec<Task> = Vec::new();
+    for query in &queries {
+        match find_task(args.project.clone(), query).await {
+            Ok(task) => {
+                if !tasks.iter().any(|t| t.id == task.id) {
+                    tasks.push(task);
+                }
+            }
+            Err(e) => eprintln!("❌ {}: {}", query, e),
+        }
+    }
+
+    if tasks.is_empty() {
+        return Err(Error::NotFound(String::from(
+            "None of the given tasks were found",
+        )));
+    }
+
+    print_tasks(&tasks);
+
+    if !args.yes {
+        if !io::stdin().is_terminal() {
+            return Err(Error::Validation(String::from(
+                "Cannot ask for confirmation without a terminal, pass --yes",
+            )));
+        }
+
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Delete {} task(s)?", tasks.len()))
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            println!("Nothing deleted");
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for task in &tasks {
+        match delete_task(task).await {
+            Ok(_) => println!("🗑️  Deleted: {}", task.title),
+            Err(e) => {
+                failed += 1;
+                eprintln!("❌ {}: {}", task.title, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::PartialFailure {
+            failed,
+            total: tasks.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn task(start_date: Option<&str>, due_date: Option<&str>) -> Task {
+        serde_json::from_value(json!({
+            "id": "task1",
+            "etag": "abc",
+            "isAllDay": false,
+            "title": "Code review",
+            "kind": "TEXT",
+            "startDate": start_date,
+            "dueDate": due_date,
+            "priority": 0,
+            "projectId": "proj1",
+            "status": 0,
+            "sortOrder": 0
+        }))
+        .unwrap()
+    }
+
+    fn duration(duration: &str) -> ScheduleArgs {
+        ScheduleArgs {
+            duration: Some(String::from(duration)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fills_the_due_date_from_start_and_duration() {
+        let args = ScheduleArgs {
+            start: Some(String::from("2025-12-16 10:00am")),
+            ..duration("1h30m")
+        };
+        let schedule = parse_schedule(&args, "UTC", None).unwrap();
+        assert_eq!(
+            schedule.start_date.as_deref(),
+            Some("2025-12-16T10:00:00+0000")
+        );
+        assert_eq!(
+            schedule.due_date.as_deref(),
+            Some("2025-12-16T11:30:00+0000")
+        );
+    }
+
+    #[test]
+    fn needs_a_date_for_a_duration_on_create() {
+        let error = parse_schedule(&duration("1h"), "UTC", None).err();
+        assert!(matches!(error, Some(Error::Validation(_))));
+    }
+
+    #[test]
+    fn counts_an_updated_duration_from_the_existing_start() {
+        let current = task(
+            Some("2025-12-16T10:00:00.000+0000"),
+            Some("2025-12-16T10:30:00.000+0000"),
+        );
+        let schedule = parse_schedule(&duration("1h30m"), "UTC", Some(&current)).unwrap();
+        assert_eq!(
+            schedule.start_date.as_deref(),
+            Some("2025-12-16T10:00:00+0000")
+        );
+        assert_eq!(
+            schedule.due_date.as_deref(),
+            Some("2025-12-16T11:30:00+0000")
+        );
+    }
+
+    #[test]
+    fn counts_an_updated_duration_back_from_the_existing_due_date() {
+        let current = task(None, Some("2025-12-16T14:00:00.000+0000"));
+        let schedule = parse_schedule(&duration("2h"), "UTC", Some(&current)).unwrap();
+        assert_eq!(
+            schedule.start_date.as_deref(),
+            Some("2025-12-16T12:00:00+0000")
+        );
+        assert_eq!(
+            schedule.due_date.as_deref(),
+            Some("2025-12-16T14:00:00+0000")
+        );
+    }
+
+    #[test]
+    fn passes_the_all_day_flag_both_ways() {
+        let all_day = |value| ScheduleArgs {
+            all_day: value,
+            ..Default::default()
+        };
+        let schedule = |args| parse_schedule(&args, "UTC", None).unwrap().is_all_day;
+        assert_eq!(schedule(all_day(Some(true))), Some(true));
+        assert_eq!(schedule(all_day(Some(false))), Some(false));
+        assert_eq!(schedule(all_day(None)), None);
+    }
+}
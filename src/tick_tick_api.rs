@@ -1,24 +1,80 @@
+use std::time::Duration;
+
 use clap::ValueEnum;
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::error::{Error, Result};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Project {
     pub id: String,
     pub name: String,
     pub kind: Option<String>,
     pub sort_order: i64,
+    pub color: Option<String>,
+    pub closed: Option<bool>,
+    pub group_id: Option<String>,
+    pub view_mode: Option<String>,
+    pub permission: Option<String>,
+}
+
+impl Project {
+    pub fn is_archived(&self) -> bool {
+        self.closed.unwrap_or(false)
+    }
+}
+
+/// Body for creating and updating projects, only the fields that are set are sent
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_mode: Option<ViewMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProjectKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub title: String,
+    pub status: u32,
+    pub sort_order: i64,
+    pub start_date: Option<String>,
+}
+
+impl ChecklistItem {
+    pub fn is_checked(&self) -> bool {
+        self.status != 0
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTaskBody {
     pub title: String,
+    pub content: Option<String>,
     pub project_id: Option<String>,
     pub tags: Vec<String>,
+    pub start_date: Option<String>,
     pub due_date: Option<String>,
+    pub is_all_day: Option<bool>,
     pub time_zone: String,
     pub priority: Option<u32>,
+    pub items: Option<Vec<ChecklistItem>>,
+    pub repeat_flag: Option<String>,
+    pub reminders: Option<Vec<String>>,
+    pub column_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -30,16 +86,39 @@ pub struct Task {
     pub content: Option<String>,
     pub title: String,
     pub kind: String,
+    pub start_date: Option<String>,
     pub due_date: Option<String>,
     pub priority: u32,
     pub project_id: String,
     pub status: u32,
     pub tags: Option<Vec<String>>,
     pub sort_order: i64,
+    pub repeat_flag: Option<String>,
+    pub reminders: Option<Vec<String>>,
+    pub column_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<ChecklistItem>>,
+}
+
+impl Task {
+    /// Checked and total checklist items, `None` when the task has no checklist
+    pub fn item_progress(&self) -> Option<(usize, usize)> {
+        let items = self.items.as_ref().filter(|items| !items.is_empty())?;
+        let checked = items.iter().filter(|item| item.is_checked()).count();
+        Some((checked, items.len()))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+pub struct MoveTaskBody {
+    pub from_project_id: String,
+    pub to_project_id: String,
+    pub task_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Column {
     pub id: String,
     pub project_id: String,
@@ -60,3 +139,774 @@ pub enum TaskPriority {
     Mid,
     High,
 }
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewMode {
+    List,
+    Kanban,
+    Timeline,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProjectKind {
+    Task,
+    Note,
+}
+
+/// Longest wait between two attempts, whatever the server asks for
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// How idempotent requests are retried on connection errors, 5xx and 429.
+/// The wait doubles after every attempt, starting at `backoff`.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Send every request once
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    fn wait(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(MAX_RETRY_WAIT)
+    }
+
+    /// After a 429, the server's `Retry-After` wins over the backoff
+    fn rate_limit_wait(&self, attempt: u32, retry_after: Option<u64>) -> Duration {
+        retry_after.map_or(self.wait(attempt), |seconds| {
+            Duration::from_secs(seconds).min(MAX_RETRY_WAIT)
+        })
+    }
+}
+
+/// Typed access to the TickTick open API.
+/// The `reqwest::Client` carries the authorization header, the base URL is the API host.
+#[derive(Clone, Debug)]
+pub struct TickTickClient {
+    http: Client,
+    base_url: String,
+    retry: RetryPolicy,
+}
+
+impl TickTickClient {
+    pub fn new(http: Client, base_url: &str) -> Self {
+        TickTickClient {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            retry: RetryPolicy::none(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send a request and check its status.
+    /// Only idempotent requests are retried, a repeated create would duplicate the task.
+    async fn send(&self, request: RequestBuilder, idempotent: bool) -> Result<Response> {
+        let max_attempts = if idempotent {
+            self.retry.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            let Some(current) = request.try_clone() else {
+                return check_response(request.send().await?).await;
+            };
+            let result = current.send().await;
+            if attempt >= max_attempts {
+                return check_response(result?).await;
+            }
+
+            let (reason, wait) = match &result {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => (
+                    String::from("Rate limited"),
+                    self.retry.rate_limit_wait(attempt, retry_after(response)),
+                ),
+                Ok(response) if response.status().is_server_error() => (
+                    format!("Server error {}", response.status().as_u16()),
+                    self.retry.wait(attempt),
+                ),
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    (String::from("Connection failed"), self.retry.wait(attempt))
+                }
+                _ => return check_response(result?).await,
+            };
+
+            eprintln!(
+                "⏳ {}, retrying in {:.1}s ({}/{})",
+                reason,
+                wait.as_secs_f32(),
+                attempt,
+                max_attempts - 1
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/open/v1/{}", self.base_url, path)
+    }
+
+    pub async fn get_projects(&self) -> Result<Vec<Project>> {
+        let request = self.http.get(self.url("project"));
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    pub async fn get_project(&self, project_id: &str) -> Result<Project> {
+        let request = self.http.get(self.url(&format!("project/{}", project_id)));
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    /// Tasks and kanban columns of a project, `inbox` is accepted as project id
+    pub async fn get_project_data(&self, project_id: &str) -> Result<ProjectTaskResponse> {
+        let request = self
+            .http
+            .get(self.url(&format!("project/{}/data", project_id)));
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    pub async fn create_project(&self, body: &ProjectBody) -> Result<Project> {
+        let request = self.http.post(self.url("project")).json(body);
+        Ok(self.send(request, false).await?.json().await?)
+    }
+
+    pub async fn update_project(&self, project_id: &str, body: &ProjectBody) -> Result<Project> {
+        let request = self
+            .http
+            .post(self.url(&format!("project/{}", project_id)))
+            .json(body);
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    pub async fn delete_project(&self, project_id: &str) -> Result<()> {
+        let request = self
+            .http
+            .delete(self.url(&format!("project/{}", project_id)));
+        self.send(request, true).await?;
+        Ok(())
+    }
+
+    pub async fn get_task(&self, project_id: &str, task_id: &str) -> Result<Task> {
+        let request = self
+            .http
+            .get(self.url(&format!("project/{}/task/{}", project_id, task_id)));
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    pub async fn create_task(&self, body: &CreateTaskBody) -> Result<Task> {
+        let request = self.http.post(self.url("task")).json(body);
+        Ok(self.send(request, false).await?.json().await?)
+    }
+
+    /// Send the full task back, the task id and etag come from a previous read
+    pub async fn update_task(&self, task: &Task) -> Result<Task> {
+        let request = self
+            .http
+            .post(self.url(&format!("task/{}", task.id)))
+            .json(task);
+        Ok(self.send(request, true).await?.json().await?)
+    }
+
+    pub async fn complete_task(&self, project_id: &str, task_id: &str) -> Result<()> {
+        let request = self
+            .http
+            .post(self.url(&format!("project/{}/task/{}/complete", project_id, task_id)));
+        self.send(request, true).await?;
+        Ok(())
+    }
+
+    pub async fn delete_task(&self, project_id: &str, task_id: &str) -> Result<()> {
+        let request = self
+            .http
+            .delete(self.url(&format!("project/{}/task/{}", project_id, task_id)));
+        self.send(request, true).await?;
+        Ok(())
+    }
+
+    /// Move tasks between projects in a single request
+    pub async fn move_tasks(&self, moves: &[MoveTaskBody]) -> Result<()> {
+        let request = self.http.post(self.url("task/move")).json(moves);
+        self.send(request, false).await?;
+        Ok(())
+    }
+}
+
+/// Seconds the server asked to wait, only the delay form of `Retry-After` is supported
+fn retry_after(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Turn unsuccessful API responses into errors, successful ones are returned as they are
+async fn check_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after(&response);
+
+    let body = response.text().await.unwrap_or_default();
+    let message = if body.is_empty() {
+        status
+            .canonical_reason()
+            .unwrap_or("unknown error")
+            .to_string()
+    } else {
+        body
+    };
+
+    Err(match status {
+        StatusCode::UNAUTHORIZED => Error::AuthExpired,
+        StatusCode::NOT_FOUND => Error::NotFound(format!("Not found: {}", message)),
+        StatusCode::TOO_MANY_REQUESTS => Error::RateLimited { retry_after },
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+            Error::Validation(format!("TickTick rejected the request: {}", message))
+        }
+        status => Error::Api {
+            status: status.as_u16(),
+            message,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        net::Ipv4Addr,
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use warp::{
+        http::{Method, Response},
+        hyper::body::Bytes,
+        path::FullPath,
+        Filter,
+    };
+
+    use super::*;
+
+    /// A canned reply, the last one is repeated once the queue runs out
+    #[derive(Clone)]
+    struct MockResponse {
+        status: u16,
+        body: String,
+        retry_after: Option<&'static str>,
+    }
+
+    fn reply(status: u16, body: &str) -> MockResponse {
+        MockResponse {
+            status,
+            body: body.to_string(),
+            retry_after: None,
+        }
+    }
+
+    #[derive(Debug)]
+    struct Recorded {
+        method: Method,
+        path: String,
+        body: Option<Value>,
+    }
+
+    /// A local API server that records every request it receives
+    #[derive(Clone)]
+    struct MockServer {
+        client: TickTickClient,
+        requests: Arc<Mutex<Vec<Recorded>>>,
+    }
+
+    impl MockServer {
+        async fn start(responses: Vec<MockResponse>) -> MockServer {
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let responses = Arc::new(Mutex::new(VecDeque::from(responses)));
+
+            let recorded = requests.clone();
+            let route = warp::method()
+                .and(warp::path::full())
+                .and(warp::body::bytes())
+                .map(move |method: Method, path: FullPath, body: Bytes| {
+                    recorded.lock().unwrap().push(Recorded {
+                        method,
+                        path: path.as_str().to_string(),
+                        body: serde_json::from_slice(&body).ok(),
+                    });
+
+                    let mut responses = responses.lock().unwrap();
+                    let next = if responses.len() > 1 {
+                        responses.pop_front().unwrap()
+                    } else {
+                        responses.front().cloned().unwrap_or(reply(200, "{}"))
+                    };
+                    let mut response = Response::builder().status(next.status);
+                    if let Some(seconds) = next.retry_after {
+                        response = response.header("Retry-After", seconds);
+                    }
+                    response.body(next.body).unwrap()
+                });
+
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let base_url = format!("http://{}/", listener.local_addr().unwrap());
+            tokio::spawn(warp::serve(route).incoming(listener).run());
+
+            MockServer {
+                client: TickTickClient::new(Client::new(), &base_url),
+                requests,
+            }
+        }
+
+        fn requests(&self) -> Vec<Recorded> {
+            std::mem::take(&mut *self.requests.lock().unwrap())
+        }
+
+        /// The single request the test made
+        fn request(&self) -> Recorded {
+            let mut requests = self.requests();
+            assert_eq!(requests.len(), 1, "expected one request: {:?}", requests);
+            requests.remove(0)
+        }
+    }
+
+    fn task_json() -> Value {
+        json!({
+            "id": "task1",
+            "etag": "abc",
+            "isAllDay": false,
+            "title": "Write tests",
+            "kind": "TEXT",
+            "dueDate": "2025-12-16T14:00:00.000+0000",
+            "priority": 5,
+            "projectId": "proj1",
+            "status": 0,
+            "tags": ["dev"],
+            "sortOrder": -1099511627776i64,
+            "items": [
+                { "id": "item1", "title": "Mock server", "status": 1, "sortOrder": 0 },
+                { "id": "item2", "title": "Status mapping", "status": 0, "sortOrder": 1 }
+            ]
+        })
+    }
+
+    fn create_body() -> CreateTaskBody {
+        CreateTaskBody {
+            title: String::from("Write tests"),
+            content: None,
+            project_id: Some(String::from("proj1")),
+            tags: vec![String::from("dev")],
+            start_date: None,
+            due_date: None,
+            is_all_day: None,
+            time_zone: String::from("UTC"),
+            priority: Some(5),
+            items: None,
+            repeat_flag: None,
+            reminders: None,
+            column_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn gets_projects() {
+        let body = json!([
+            { "id": "proj1", "name": "Work", "sortOrder": 0, "closed": true },
+            { "id": "proj2", "name": "Home", "sortOrder": 1, "kind": "TASK", "viewMode": "list" }
+        ]);
+        let server = MockServer::start(vec![reply(200, &body.to_string())]).await;
+
+        let projects = server.client.get_projects().await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/open/v1/project");
+        assert_eq!(projects.len(), 2);
+        assert!(projects[0].is_archived());
+        assert_eq!(projects[1].view_mode.as_deref(), Some("list"));
+    }
+
+    #[tokio::test]
+    async fn gets_a_single_project() {
+        let body = json!({ "id": "proj1", "name": "Work", "sortOrder": 0, "color": "#F18181" });
+        let server = MockServer::start(vec![reply(200, &body.to_string())]).await;
+
+        let project = server.client.get_project("proj1").await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/open/v1/project/proj1");
+        assert_eq!(project.name, "Work");
+        assert_eq!(project.color.as_deref(), Some("#F18181"));
+    }
+
+    #[tokio::test]
+    async fn decodes_project_data() {
+        let body = json!({
+            "project": { "id": "inbox1", "name": "Inbox", "sortOrder": 0 },
+            "columns": [{ "id": "col1", "projectId": "inbox1", "name": "Todo", "sortOrder": 0 }],
+            "tasks": [task_json()]
+        });
+        let server = MockServer::start(vec![reply(200, &body.to_string())]).await;
+
+        let data = server.client.get_project_data("inbox").await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::GET);
+        assert_eq!(request.path, "/open/v1/project/inbox/data");
+        assert_eq!(data.columns[0].name, "Todo");
+        let tasks = data.tasks.unwrap();
+        assert_eq!(tasks[0].title, "Write tests");
+        assert_eq!(tasks[0].tags, Some(vec![String::from("dev")]));
+        assert_eq!(tasks[0].item_progress(), Some((1, 2)));
+    }
+
+    #[tokio::test]
+    async fn decodes_project_data_without_tasks() {
+        let server = MockServer::start(vec![reply(200, r#"{"columns":[]}"#)]).await;
+
+        let data = server.client.get_project_data("proj1").await.unwrap();
+
+        assert!(data.columns.is_empty());
+        assert!(data.tasks.is_none());
+    }
+
+    #[tokio::test]
+    async fn creates_tasks() {
+        let server = MockServer::start(vec![reply(200, &task_json().to_string())]).await;
+
+        let task = server.client.create_task(&create_body()).await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/open/v1/task");
+        let body = request.body.unwrap();
+        assert_eq!(body["title"], "Write tests");
+        assert_eq!(body["projectId"], "proj1");
+        assert_eq!(body["timeZone"], "UTC");
+        assert_eq!(body["priority"], 5);
+        assert_eq!(task.id, "task1");
+        assert_eq!(task.project_id, "proj1");
+        assert_eq!(
+            task.due_date.as_deref(),
+            Some("2025-12-16T14:00:00.000+0000")
+        );
+    }
+
+    #[tokio::test]
+    async fn updates_tasks_with_the_full_task() {
+        let server = MockServer::start(vec![reply(200, &task_json().to_string())]).await;
+        let mut task: Task = serde_json::from_value(task_json()).unwrap();
+        task.title = String::from("Write more tests");
+
+        server.client.update_task(&task).await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/open/v1/task/task1");
+        let body = request.body.unwrap();
+        assert_eq!(body["id"], "task1");
+        assert_eq!(body["etag"], "abc");
+        assert_eq!(body["title"], "Write more tests");
+        assert_eq!(body["items"][0]["title"], "Mock server");
+    }
+
+    #[tokio::test]
+    async fn gets_completes_and_deletes_tasks() {
+        let server = MockServer::start(vec![
+            reply(200, &task_json().to_string()),
+            reply(200, ""),
+            reply(200, ""),
+        ])
+        .await;
+
+        server.client.get_task("proj1", "task1").await.unwrap();
+        server.client.complete_task("proj1", "task1").await.unwrap();
+        server.client.delete_task("proj1", "task1").await.unwrap();
+
+        let requests: Vec<(Method, String)> = server
+            .requests()
+            .into_iter()
+            .map(|request| (request.method, request.path))
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    Method::GET,
+                    String::from("/open/v1/project/proj1/task/task1")
+                ),
+                (
+                    Method::POST,
+                    String::from("/open/v1/project/proj1/task/task1/complete")
+                ),
+                (
+                    Method::DELETE,
+                    String::from("/open/v1/project/proj1/task/task1")
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn moves_tasks_in_one_request() {
+        let server = MockServer::start(vec![reply(200, "")]).await;
+        let moves = [
+            MoveTaskBody {
+                from_project_id: String::from("proj1"),
+                to_project_id: String::from("proj2"),
+                task_id: String::from("task1"),
+            },
+            MoveTaskBody {
+                from_project_id: String::from("proj1"),
+                to_project_id: String::from("proj2"),
+                task_id: String::from("task2"),
+            },
+        ];
+
+        server.client.move_tasks(&moves).await.unwrap();
+
+        let request = server.request();
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.path, "/open/v1/task/move");
+        assert_eq!(
+            request.body.unwrap(),
+            json!([
+                { "fromProjectId": "proj1", "toProjectId": "proj2", "taskId": "task1" },
+                { "fromProjectId": "proj1", "toProjectId": "proj2", "taskId": "task2" }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_only_the_project_fields_that_are_set() {
+        let project = json!({ "id": "proj1", "name": "Work", "sortOrder": 0 }).to_string();
+        let server = MockServer::start(vec![reply(200, &project)]).await;
+        let body = ProjectBody {
+            name: Some(String::from("Work")),
+            view_mode: Some(ViewMode::Kanban),
+            kind: Some(ProjectKind::Task),
+            ..Default::default()
+        };
+
+        server.client.create_project(&body).await.unwrap();
+        server.client.update_project("proj1", &body).await.unwrap();
+        server.client.delete_project("proj1").await.unwrap();
+
+        let requests = server.requests();
+        let expected = json!({ "name": "Work", "viewMode": "kanban", "kind": "TASK" });
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].path, "/open/v1/project");
+        assert_eq!(requests[0].body.as_ref(), Some(&expected));
+        assert_eq!(requests[1].method, Method::POST);
+        assert_eq!(requests[1].path, "/open/v1/project/proj1");
+        assert_eq!(requests[1].body.as_ref(), Some(&expected));
+        assert_eq!(requests[2].method, Method::DELETE);
+        assert_eq!(requests[2].path, "/open/v1/project/proj1");
+    }
+
+    async fn error_for(status: u16, body: &str) -> Error {
+        let server = MockServer::start(vec![reply(status, body)]).await;
+        server.client.get_projects().await.unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn maps_unauthorized_to_auth_expired() {
+        assert!(matches!(error_for(401, "").await, Error::AuthExpired));
+    }
+
+    #[tokio::test]
+    async fn maps_not_found() {
+        match error_for(404, "task not found").await {
+            Error::NotFound(message) => assert_eq!(message, "Not found: task not found"),
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_rejected_requests_to_validation() {
+        for status in [400, 422] {
+            match error_for(status, r#"{"errorMessage":"title is required"}"#).await {
+                Error::Validation(message) => assert!(message.contains("title is required")),
+                error => panic!("unexpected error for {}: {:?}", status, error),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_other_statuses_to_api_errors() {
+        match error_for(500, "").await {
+            Error::Api { status, message } => {
+                assert_eq!(status, 500);
+                assert_eq!(message, "Internal Server Error");
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_undecodable_responses() {
+        let error = error_for(200, "<html>maintenance</html>").await;
+        assert!(matches!(error, Error::Api { .. }), "{:?}", error);
+    }
+
+    fn retrying(server: MockServer, max_attempts: u32) -> TickTickClient {
+        server.client.clone().with_retry(RetryPolicy {
+            max_attempts,
+            backoff: Duration::from_millis(1),
+        })
+    }
+
+    #[test]
+    fn doubles_the_backoff_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.wait(1), Duration::from_millis(500));
+        assert_eq!(policy.wait(2), Duration::from_secs(1));
+        assert_eq!(policy.wait(3), Duration::from_secs(2));
+        assert_eq!(policy.wait(10), MAX_RETRY_WAIT);
+    }
+
+    #[test]
+    fn caps_retry_after() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.rate_limit_wait(1, Some(5)), Duration::from_secs(5));
+        assert_eq!(policy.rate_limit_wait(1, Some(3600)), MAX_RETRY_WAIT);
+        assert_eq!(policy.rate_limit_wait(2, None), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_up_to_max_attempts() {
+        let server = MockServer::start(vec![reply(503, "")]).await;
+        let client = retrying(server.clone(), 3);
+
+        let error = client.get_projects().await.unwrap_err();
+
+        assert!(
+            matches!(error, Error::Api { status: 503, .. }),
+            "{:?}",
+            error
+        );
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn succeeds_after_a_retried_server_error() {
+        let server = MockServer::start(vec![reply(502, ""), reply(200, "[]")]).await;
+        let client = retrying(server.clone(), 3);
+
+        let projects = client.get_projects().await.unwrap();
+
+        assert!(projects.is_empty());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sends_once_without_a_retry_policy() {
+        let server = MockServer::start(vec![reply(503, "")]).await;
+
+        server.client.get_projects().await.unwrap_err();
+
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        for status in [400, 401, 404, 422] {
+            let server = MockServer::start(vec![reply(status, "")]).await;
+            let client = retrying(server.clone(), 3);
+
+            client.get_task("proj1", "task1").await.unwrap_err();
+
+            assert_eq!(server.requests().len(), 1, "status {}", status);
+        }
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_creating_tasks() {
+        let server = MockServer::start(vec![reply(503, ""), reply(200, "{}")]).await;
+        let client = retrying(server.clone(), 3);
+
+        let error = client.create_task(&create_body()).await.unwrap_err();
+
+        assert!(
+            matches!(error, Error::Api { status: 503, .. }),
+            "{:?}",
+            error
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_moving_tasks() {
+        let server = MockServer::start(vec![reply(429, ""), reply(200, "")]).await;
+        let client = retrying(server.clone(), 3);
+        let moves = [MoveTaskBody {
+            from_project_id: String::from("proj1"),
+            to_project_id: String::from("proj2"),
+            task_id: String::from("task1"),
+        }];
+
+        let error = client.move_tasks(&moves).await.unwrap_err();
+
+        assert!(matches!(error, Error::RateLimited { .. }), "{:?}", error);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn waits_as_long_as_retry_after_asks() {
+        let mut limited = reply(429, "");
+        limited.retry_after = Some("1");
+        let server = MockServer::start(vec![limited, reply(200, "[]")]).await;
+        let client = retrying(server.clone(), 2);
+
+        let started = std::time::Instant::now();
+        client.get_projects().await.unwrap();
+
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reports_retry_after_once_attempts_run_out() {
+        let mut limited = reply(429, "");
+        limited.retry_after = Some("7");
+        let server = MockServer::start(vec![limited]).await;
+
+        let error = server.client.get_projects().await.unwrap_err();
+
+        assert!(
+            matches!(
+                error,
+                Error::RateLimited {
+                    retry_after: Some(7)
+                }
+            ),
+            "{:?}",
+            error
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::io::{self};
+
+use ratatui::{
+    crossterm::{
+        event::{self, Event, KeyCode},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Direction, Layout},
+    prelude::CrosstermBackend,
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    error::Result,
+    services,
+    tick_tick_api::{Column, Task},
+};
+
+/// Kanban board: one lane per column, tasks without a column sit in the first lane
+pub struct BoardView {
+    columns: Vec<Column>,
+    lanes: Vec<Vec<Task>>,
+    states: Vec<ListState>,
+    lane: usize,
+    status: String,
+}
+
+fn priority_color(priority: &u32) -> Color {
+    match priority {
+        1 => Color::Blue,
+        3 => Color::Yellow,
+        5 => Color::Red,
+        _ => Color::Reset,
+    }
+}
+
+impl BoardView {
+    fn new(mut columns: Vec<Column>, tasks: Vec<Task>) -> BoardView {
+        columns.sort_by_key(|c| c.sort_order);
+
+        let mut lanes: Vec<Vec<Task>> = vec![Vec::new(); columns.len()];
+        for task in tasks {
+            let lane = task
+                .column_id
+                .as_ref()
+                .and_then(|id| columns.iter().position(|c| &c.id == id))
+                .unwrap_or(0);
+            lanes[lane].push(task);
+        }
+
+        let states = lanes
+            .iter()
+            .map(|lane| {
+                let mut state = ListState::default();
+                if !lane.is_empty() {
+                    state.select(Some(0));
+                }
+                state
+            })
+            .collect();
+
+        BoardView {
+            columns,
+            lanes,
+            states,
+            lane: 0,
+            status: String::from("h/l: lane  j/k: task  H/L: move task  q: quit"),
+        }
+    }
+
+    pub fn next_lane(&mut self) {
+        self.lane = (self.lane + 1) % self.lanes.len();
+    }
+
+    pub fn previous_lane(&mut self) {
+        self.lane = (self.lane + self.lanes.len() - 1) % self.lanes.len();
+    }
+
+    pub fn next(&mut self) {
+        let len = self.lanes[self.lane].len();
+        let state = &mut self.states[self.lane];
+        if len > 0 {
+            state.select(Some(state.selected().map_or(0, |i| (i + 1) % len)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.lanes[self.lane].len();
+        let state = &mut self.states[self.lane];
+        if len > 0 {
+            state.select(Some(state.selected().map_or(0, |i| (i + len - 1) % len)));
+        }
+    }
+
+    /// Move the selected task to the lane on the left (-1) or right (+1) and save it
+    async fn move_selected(&mut self, step: isize) {
+        let target = self.lane as isize + step;
+        if target < 0 || target as usize >= self.lanes.len() {
+            return;
+        }
+        let target = target as usize;
+
+        let Some(index) = self.states[self.lane].selected() else {
+            return;
+        };
+        let Some(task) = self.lanes[self.lane].get(index) else {
+            return;
+        };
+
+        let mut task = task.clone();
+        task.column_id = Some(self.columns[target].id.clone());
+
+        match services::tasks::save_task(&task).await {
+            Ok(saved) => {
+                self.lanes[self.lane].remove(index);
+                let remaining = self.lanes[self.lane].len();
+                self.states[self.lane].select(match remaining {
+                    0 => None,
+                    _ => Some(index.min(remaining - 1)),
+                });
+
+                self.status = format!("Moved '{}' to {}", saved.title, self.columns[target].name);
+                self.lanes[target].push(saved);
+                self.states[target].select(Some(self.lanes[target].len() - 1));
+                self.lane = target;
+            }
+            Err(e) => self.status = format!("❌ Cannot move '{}': {}", task.title, e),
+        }
+    }
+}
+
+fn draw_board(frame: &mut Frame, view: &mut BoardView) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(frame.area());
+
+    let lane_count = view.lanes.len() as u32;
+    let lanes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints((0..lane_count).map(|_| Constraint::Ratio(1, lane_count)))
+        .split(rows[0]);
+
+    for (i, column) in view.columns.iter().enumerate() {
+        let items: Vec<ListItem> = view.lanes[i]
+            .iter()
+            .map(|task| {
+                let title = match task.item_progress() {
+                    Some((checked, total)) => format!("{} ({}/{})", task.title, checked, total),
+                    None => task.title.clone(),
+                };
+                ListItem::new(title).style(Style::default().fg(priority_color(&task.priority)))
+            })
+            .collect();
+
+        let border_style = if i == view.lane {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!("{} ({})", column.name, view.lanes[i].len()))
+                    .borders(Borders::ALL)
+                    .border_style(border_style),
+            )
+            .highlight_style(if i == view.lane {
+                Style::default().bg(Color::White).fg(Color::Black)
+            } else {
+                Style::default()
+            });
+
+        frame.render_stateful_widget(list, lanes[i], &mut view.states[i]);
+    }
+
+    frame.render_widget(Paragraph::new(view.status.clone()), rows[1]);
+}
+
+pub async fn render_board(columns: Vec<Column>, tasks: Vec<Task>) -> Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+
+    let backend = CrosstermBackend::new(stdout);
+
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut view = BoardView::new(columns, tasks);
+
+    loop {
+        terminal.draw(|f| draw_board(f, &mut view))?;
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Right | KeyCode::Char('l') => view.next_lane(),
+                KeyCode::Left | KeyCode::Char('h') => view.previous_lane(),
+                KeyCode::Down | KeyCode::Char('j') => view.next(),
+                KeyCode::Up | KeyCode::Char('k') => view.previous(),
+                KeyCode::Char('L') | KeyCode::Char('>') => view.move_selected(1).await,
+                KeyCode::Char('H') | KeyCode::Char('<') => view.move_selected(-1).await,
+                _ => {}
+            }
+        }
+    }
+
+    // Restore Terminal
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    Ok(())
+}
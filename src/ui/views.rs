@@ -1,5 +1,9 @@
-use std::io::{self};
+use std::{
+    collections::HashMap,
+    io::{self},
+};
 
+use comfy_table::{presets::UTF8_FULL, Table as PlainTable};
 use ratatui::{
     crossterm::{
         event::{self, Event, KeyCode},
@@ -13,30 +17,76 @@ use ratatui::{
     Frame, Terminal,
 };
 
-use crate::tick_tick_api::Task;
+use crate::{
+    error::Result,
+    recurrence::describe_repeat,
+    reminder::describe_reminder,
+    tick_tick_api::{Column, Project, Task},
+};
 
 pub struct TaskView {
     items: Vec<Task>,
+    project_names: Option<HashMap<String, String>>,
     state: TableState,
     load_details: bool,
     selected: Option<Task>,
 }
 
+fn parse_progress(task: &Task) -> String {
+    match task.item_progress() {
+        Some((checked, total)) => format!("{}/{}", checked, total),
+        None => String::from("―"),
+    }
+}
+
+fn format_items(task: &Task) -> String {
+    task.items
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, item)| {
+            let mark = if item.is_checked() { "x" } else { " " };
+            format!("{}. [{}] {}", i + 1, mark, item.title)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn parse_repeat(task: &Task) -> String {
+    match task.repeat_flag.as_deref() {
+        Some(rule) if !rule.is_empty() => describe_repeat(rule),
+        _ => String::from("-"),
+    }
+}
+
+fn parse_reminders(task: &Task) -> String {
+    let reminders: Vec<String> = task
+        .reminders
+        .iter()
+        .flatten()
+        .map(|trigger| describe_reminder(trigger))
+        .collect();
+    if reminders.is_empty() {
+        return String::from("-");
+    }
+    reminders.join(", ")
+}
+
 fn parse_priority(priority: &u32) -> String {
-    let value = match priority {
+    match priority {
         0 => String::from("―"),
         1 => String::from("🔵 Low"),
         3 => String::from("🟡 Medium"),
         5 => String::from("🔴 High"),
         _ => String::from(""),
-    };
-    value
+    }
 }
 
 impl TaskView {
-    fn new(tasks: Vec<Task>) -> TaskView {
+    fn new(tasks: Vec<Task>, project_names: Option<HashMap<String, String>>) -> TaskView {
         TaskView {
             items: tasks,
+            project_names,
             state: TableState::default(),
             selected: None,
             load_details: false,
@@ -103,27 +153,37 @@ fn draw_tasks(frame: &mut Frame, view: &mut TaskView) {
         .items
         .iter()
         .map(|item| {
-            Row::new(vec![
+            let mut cells = vec![
                 item.title.clone(),
                 parse_priority(&item.priority),
                 item.due_date.clone().unwrap_or(String::from("_")),
-            ])
+                parse_progress(item),
+            ];
+            if let Some(names) = &view.project_names {
+                let name = names.get(&item.project_id).unwrap_or(&item.project_id);
+                cells.push(name.clone());
+            }
+            Row::new(cells)
         })
         .collect();
 
-    let header: Row =
-        Row::new(vec!["Title", "Priority", "Due"]).style(Style::default().fg(Color::Yellow));
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Percentage(40),
-            Constraint::Percentage(10),
-            Constraint::Percentage(20),
-        ],
-    )
-    .header(header)
-    .block(Block::default().title("Tasks").borders(Borders::ALL))
-    .row_highlight_style(Style::default().bg(Color::White).fg(Color::Black));
+    let mut header_cells = vec!["Title", "Priority", "Due", "Items"];
+    let mut widths = vec![
+        Constraint::Percentage(40),
+        Constraint::Percentage(10),
+        Constraint::Percentage(20),
+        Constraint::Percentage(10),
+    ];
+    if view.project_names.is_some() {
+        header_cells.push("Project");
+        widths.push(Constraint::Percentage(20));
+    }
+
+    let header: Row = Row::new(header_cells).style(Style::default().fg(Color::Yellow));
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().title("Tasks").borders(Borders::ALL))
+        .row_highlight_style(Style::default().bg(Color::White).fg(Color::Black));
 
     frame.render_stateful_widget(table, chunks[0], &mut view.state);
 
@@ -131,11 +191,16 @@ fn draw_tasks(frame: &mut Frame, view: &mut TaskView) {
         let selected = view.selected.clone().unwrap();
 
         let details_widget = Paragraph::new(format!(
-            "Title: {}\nPriority: {}\nDue Date:{}\nDescription: {}",
+            "Title: {}\nPriority: {}\nStart Date: {}\nDue Date:{}\nRepeat: {}\nReminders: {}\nDescription: {}\nChecklist ({}):\n{}",
             selected.title,
             parse_priority(&selected.priority),
-            selected.due_date.unwrap_or(String::from("-")),
-            selected.content.unwrap_or(String::from(""))
+            selected.start_date.clone().unwrap_or(String::from("-")),
+            selected.due_date.clone().unwrap_or(String::from("-")),
+            parse_repeat(&selected),
+            parse_reminders(&selected),
+            selected.content.clone().unwrap_or(String::from("")),
+            parse_progress(&selected),
+            format_items(&selected)
         ))
         .block(Block::default().borders(Borders::ALL).title("Details"))
         .wrap(Wrap { trim: true });
@@ -143,7 +208,128 @@ fn draw_tasks(frame: &mut Frame, view: &mut TaskView) {
         frame.render_widget(details_widget, chunks[1]);
     }
 }
-pub fn render_tasks(tasks: Vec<Task>) -> Result<(), Box<dyn std::error::Error>> {
+
+/// Print tasks as a plain table, used when the interactive view doesn't fit (e.g. confirmations)
+pub fn print_tasks(tasks: &[Task]) {
+    let mut table = PlainTable::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["ID", "Title", "Priority", "Due"]);
+
+    for task in tasks {
+        table.add_row(vec![
+            task.id.clone(),
+            task.title.clone(),
+            parse_priority(&task.priority),
+            task.due_date.clone().unwrap_or(String::from("_")),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+/// Print projects as a plain table
+pub fn print_projects(projects: &[Project]) {
+    let mut table = PlainTable::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["ID", "Name", "Kind", "View", "Color", "Status"]);
+
+    for project in projects {
+        let status = if project.is_archived() {
+            "Archived"
+        } else {
+            "Active"
+        };
+        table.add_row(vec![
+            project.id.clone(),
+            project.name.clone(),
+            project.kind.clone().unwrap_or(String::from("-")),
+            project.view_mode.clone().unwrap_or(String::from("-")),
+            project.color.clone().unwrap_or(String::from("-")),
+            status.to_string(),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+/// Print kanban columns in board order
+pub fn print_columns(columns: &[Column]) {
+    let mut table = PlainTable::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_header(vec!["ID", "Name", "Sort Order"]);
+
+    for column in columns {
+        table.add_row(vec![
+            column.id.clone(),
+            column.name.clone(),
+            column.sort_order.to_string(),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+fn parse_status(status: &u32) -> String {
+    match status {
+        0 => String::from("Open"),
+        2 => String::from("✅ Completed"),
+        _ => String::from("―"),
+    }
+}
+
+/// Print every field of a task as a detail sheet
+pub fn print_task_details(task: &Task, project_name: &str) {
+    let tags = task.tags.clone().unwrap_or_default();
+    let fields = vec![
+        ("ID", task.id.clone()),
+        ("Title", task.title.clone()),
+        ("Project", project_name.to_string()),
+        ("Status", parse_status(&task.status)),
+        ("Priority", parse_priority(&task.priority)),
+        (
+            "Start",
+            task.start_date.clone().unwrap_or(String::from("-")),
+        ),
+        ("Due", task.due_date.clone().unwrap_or(String::from("-"))),
+        ("Repeat", parse_repeat(task)),
+        ("Reminders", parse_reminders(task)),
+        (
+            "All Day",
+            if task.is_all_day { "Yes" } else { "No" }.to_string(),
+        ),
+        (
+            "Tags",
+            if tags.is_empty() {
+                String::from("-")
+            } else {
+                tags.join(", ")
+            },
+        ),
+        ("Kind", task.kind.clone()),
+        ("Sort Order", task.sort_order.to_string()),
+        ("Etag", task.etag.clone()),
+    ];
+
+    for (label, value) in fields {
+        println!("{:<12}{}", format!("{}:", label), value);
+    }
+
+    println!("\nDescription:");
+    println!("{}", task.content.clone().unwrap_or(String::from("-")));
+
+    if task.item_progress().is_some() {
+        println!("\nChecklist ({}):", parse_progress(task));
+        println!("{}", format_items(task));
+    }
+}
+
+pub fn render_tasks(
+    tasks: Vec<Task>,
+    project_names: Option<HashMap<String, String>>,
+) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -152,7 +338,7 @@ pub fn render_tasks(tasks: Vec<Task>) -> Result<(), Box<dyn std::error::Error>>
 
     let mut terminal = Terminal::new(backend)?;
 
-    let mut view = TaskView::new(tasks);
+    let mut view = TaskView::new(tasks, project_names);
     view.state.select(Some(0)); //Start by the first row
 
     loop {